@@ -0,0 +1,217 @@
+// Campaña: lista ordenada de niveles con nombres, reglas de desbloqueo y tiempos par, más el
+// progreso guardado del jugador. El manifiesto usa el mismo estilo `clave = valor` que `[meta]`:
+//
+//   name = Monkey's Maze
+//
+//   [level]
+//   file = banana_land.map
+//   par = 15
+//
+//   [level]
+//   file = taylors_special.map
+//   name = Bonus
+//   unlock = monkey_temple.map, the_cave.map
+//
+// `unlock` puede ser `start` (disponible desde el inicio), `previous` (por defecto: hay que
+// completar el nivel anterior; el primero siempre está disponible) o una lista de archivos
+// que hay que completar.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::map::{Location, LEVEL_EXTENSIONS};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Unlock {
+    Start,
+    Previous,
+    After(Vec<String>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CampaignLevel {
+    pub file: String, // relativo a la carpeta del manifiesto
+    pub name: Option<String>, // si falta, se usa el de `[meta]`
+    pub par: Option<f32>, // segundos
+    pub unlock: Unlock,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Campaign {
+    pub name: Option<String>,
+    pub levels: Vec<CampaignLevel>,
+}
+
+/// Error en una línea del manifiesto.
+#[derive(Debug)]
+pub struct CampaignError {
+    pub at: Location,
+    pub message: String,
+}
+
+impl fmt::Display for CampaignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.at, self.message)
+    }
+}
+
+impl std::error::Error for CampaignError {}
+
+impl Campaign {
+    pub fn load(path: &str) -> Result<Self, CampaignError> {
+        let src = fs::read_to_string(path).map_err(|e| CampaignError {
+            at: Location { file: Some(path.to_string()), ..Location::default() },
+            message: e.to_string(),
+        })?;
+        Self::parse_str(&src).map_err(|mut e| {
+            e.at.file = Some(path.to_string());
+            e
+        })
+    }
+
+    pub fn parse_str(src: &str) -> Result<Self, CampaignError> {
+        let mut campaign = Campaign::default();
+        for (n, raw) in src.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+            let at = Location::new(n + 1, raw.len() - raw.trim_start().len() + 1);
+            let err = |message: String| CampaignError { at: at.clone(), message };
+
+            if line == "[level]" {
+                campaign.levels.push(CampaignLevel {
+                    file: String::new(),
+                    name: None,
+                    par: None,
+                    unlock: Unlock::Previous,
+                });
+                continue;
+            }
+            let (key, value) = line.split_once('=')
+                .ok_or_else(|| err(format!("se esperaba 'clave = valor': {line}")))?;
+            let (key, value) = (key.trim(), value.trim());
+            let Some(level) = campaign.levels.last_mut() else {
+                match key {
+                    "name" => campaign.name = Some(value.to_string()),
+                    _ => return Err(err(format!("clave desconocida antes de [level]: {key}"))),
+                }
+                continue;
+            };
+            match key {
+                "file" => level.file = value.to_string(),
+                "name" => level.name = Some(value.to_string()),
+                "par" => level.par = Some(value.parse::<f32>()
+                    .ok()
+                    .filter(|p| *p > 0.0)
+                    .ok_or_else(|| err(format!("tiempo par inválido: {value}")))?),
+                "unlock" => level.unlock = match value {
+                    "start" => Unlock::Start,
+                    "previous" => Unlock::Previous,
+                    files => Unlock::After(files.split(',').map(|f| f.trim().to_string()).collect()),
+                },
+                _ => return Err(err(format!("clave desconocida en [level]: {key}"))),
+            }
+        }
+        if let Some(i) = campaign.levels.iter().position(|l| l.file.is_empty()) {
+            return Err(CampaignError {
+                at: Location::default(),
+                message: format!("el nivel {} no tiene 'file'", i + 1),
+            });
+        }
+        Ok(campaign)
+    }
+
+    /// Campaña sin manifiesto: todos los niveles de la carpeta, en orden alfabético y
+    /// disponibles desde el inicio.
+    pub fn from_dir(dir: &str) -> io::Result<Self> {
+        let mut files: Vec<String> = fs::read_dir(dir)?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let ext = path.extension()?.to_str()?;
+                (path.is_file() && LEVEL_EXTENSIONS.contains(&ext))
+                    .then(|| path.file_name()?.to_str().map(str::to_string))?
+            })
+            .collect();
+        files.sort();
+        let levels = files.into_iter()
+            .map(|file| CampaignLevel { file, name: None, par: None, unlock: Unlock::Start })
+            .collect();
+        Ok(Self { name: None, levels })
+    }
+
+    /// Agrega al final los niveles de `dir` que no están en la campaña (por ejemplo los
+    /// creados con el editor), disponibles desde el inicio.
+    pub fn add_extras(&mut self, dir: &str) -> io::Result<()> {
+        for level in Self::from_dir(dir)?.levels {
+            if !self.levels.iter().any(|l| l.file == level.file) {
+                self.levels.push(level);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_unlocked(&self, index: usize, progress: &Progress) -> bool {
+        let Some(level) = self.levels.get(index) else { return false };
+        match &level.unlock {
+            Unlock::Start => true,
+            Unlock::Previous => index == 0 || progress.is_completed(&self.levels[index - 1].file),
+            Unlock::After(files) => files.iter().all(|f| progress.is_completed(f)),
+        }
+    }
+
+    /// Nivel que sigue a `index` si ya está desbloqueado.
+    pub fn next_unlocked(&self, index: usize, progress: &Progress) -> Option<usize> {
+        (index + 1..self.levels.len()).find(|&i| self.is_unlocked(i, progress))
+    }
+}
+
+/// Mejores tiempos del jugador por archivo de nivel; un nivel con tiempo está completado.
+/// Se guarda como líneas `archivo = segundos`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Progress {
+    best: BTreeMap<String, f32>,
+}
+
+impl Progress {
+    /// Lee el progreso guardado; si el archivo no existe o está dañado se empieza de cero.
+    pub fn load(path: &str) -> Self {
+        let best = fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let (file, time) = line.split_once('=')?;
+                Some((file.trim().to_string(), time.trim().parse().ok()?))
+            })
+            .collect();
+        Self { best }
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        if let Some(dir) = Path::new(path).parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let text: String = self.best.iter().map(|(file, time)| format!("{file} = {time}\n")).collect();
+        fs::write(path, text)
+    }
+
+    pub fn is_completed(&self, file: &str) -> bool {
+        self.best.contains_key(file)
+    }
+
+    pub fn best_time(&self, file: &str) -> Option<f32> {
+        self.best.get(file).copied()
+    }
+
+    /// Registra un nivel completado. Devuelve `true` si es un nuevo mejor tiempo.
+    pub fn record(&mut self, file: &str, time: f32) -> bool {
+        match self.best.get(file) {
+            Some(&best) if best <= time => false,
+            _ => {
+                self.best.insert(file.to_string(), time);
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+// Edición de mapas celda por celda con deshacer/rehacer. Cada cambio guarda una copia del
+// mapa completo: los niveles son pequeños y así cualquier herramienta se deshace igual.
+
+use crate::map::{Door, Facing, Spawn};
+use crate::Map;
+
+// Cambios que se pueden deshacer
+const UNDO_LIMIT: usize = 200;
+
+/// Lo que pone la herramienta actual en la celda del cursor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+    Wall(u8),
+    Erase,
+    /// Mueve la aparición; sobre la aparición la gira 90°.
+    Spawn,
+    /// Pone o quita una meta.
+    Goal,
+    Door,
+}
+
+impl Tool {
+    pub fn name(self) -> String {
+        match self {
+            Tool::Wall(id) => format!("pared {id}"),
+            Tool::Erase => "borrar".into(),
+            Tool::Spawn => "aparición".into(),
+            Tool::Goal => "meta".into(),
+            Tool::Door => "puerta".into(),
+        }
+    }
+}
+
+pub struct Editor {
+    pub map: Map,
+    pub cursor: (i32, i32),
+    pub tool: Tool,
+    pub dirty: bool, // hay cambios sin guardar
+    undo: Vec<Map>,
+    redo: Vec<Map>,
+}
+
+impl Editor {
+    pub fn new(map: Map) -> Self {
+        let cursor = (map.spawn.x, map.spawn.y);
+        Self { map, cursor, tool: Tool::Wall(1), dirty: false, undo: Vec::new(), redo: Vec::new() }
+    }
+
+    /// Mapa vacío con borde de pared 1, la aparición arriba a la izquierda y la meta abajo a
+    /// la derecha.
+    pub fn blank(w: i32, h: i32) -> Self {
+        let (w, h) = (w.max(3), h.max(3));
+        let cells = (0..h)
+            .flat_map(|y| (0..w).map(move |x| u8::from(x == 0 || y == 0 || x == w - 1 || y == h - 1)))
+            .collect();
+        let mut map = Map::new(w, h, cells, Spawn { x: 1, y: 1, facing: Facing::East });
+        map.goals.insert((w - 2, h - 2));
+        let mut editor = Self::new(map);
+        editor.dirty = true;
+        editor
+    }
+
+    pub fn move_cursor(&mut self, dx: i32, dy: i32) {
+        self.cursor.0 = (self.cursor.0 + dx).clamp(0, self.map.w - 1);
+        self.cursor.1 = (self.cursor.1 + dy).clamp(0, self.map.h - 1);
+    }
+
+    /// Aplica la herramienta en el cursor. Devuelve `false` si no cambió nada.
+    pub fn apply(&mut self) -> bool {
+        let before = self.map.clone();
+        let (x, y) = self.cursor;
+        let is_spawn = (x, y) == (self.map.spawn.x, self.map.spawn.y);
+        match self.tool {
+            // La aparición nunca queda tapada
+            Tool::Wall(_) | Tool::Door if is_spawn => {}
+            Tool::Wall(id) => {
+                self.clear_cell(x, y);
+                self.set_cell(x, y, id);
+            }
+            Tool::Erase => {
+                self.clear_cell(x, y);
+                self.set_cell(x, y, 0);
+            }
+            Tool::Spawn if is_spawn => {
+                let facing = &mut self.map.spawn.facing;
+                *facing = match *facing {
+                    Facing::North => Facing::East,
+                    Facing::East => Facing::South,
+                    Facing::South => Facing::West,
+                    Facing::West => Facing::North,
+                };
+            }
+            Tool::Spawn => {
+                self.clear_cell(x, y);
+                self.set_cell(x, y, 0);
+                self.map.spawn.x = x;
+                self.map.spawn.y = y;
+            }
+            Tool::Goal if self.map.is_goal(x, y) => { self.map.goals.remove(&(x, y)); }
+            Tool::Goal if is_spawn => {}
+            Tool::Goal => {
+                self.clear_cell(x, y);
+                self.set_cell(x, y, 0);
+                self.map.goals.insert((x, y));
+            }
+            Tool::Door => {
+                self.clear_cell(x, y);
+                self.set_cell(x, y, 0);
+                self.map.doors.insert((x, y), Door::default());
+            }
+        }
+        if self.map == before { return false; }
+        self.undo.push(before);
+        if self.undo.len() > UNDO_LIMIT { self.undo.remove(0); }
+        self.redo.clear();
+        self.dirty = true;
+        true
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let Some(map) = self.undo.pop() else { return false };
+        self.redo.push(std::mem::replace(&mut self.map, map));
+        self.dirty = true;
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let Some(map) = self.redo.pop() else { return false };
+        self.undo.push(std::mem::replace(&mut self.map, map));
+        self.dirty = true;
+        true
+    }
+
+    fn set_cell(&mut self, x: i32, y: i32, id: u8) {
+        if let Some(i) = self.map.index(x, y) {
+            self.map.cells[i] = id;
+        }
+    }
+
+    // Quita meta, puerta, llave y teletransporte de la celda. Un teletransporte se quita con
+    // su pareja para que el mapa se pueda volver a cargar.
+    fn clear_cell(&mut self, x: i32, y: i32) {
+        let map = &mut self.map;
+        map.goals.remove(&(x, y));
+        map.doors.remove(&(x, y));
+        map.keys.remove(&(x, y));
+        if let Some(t) = map.teleporters.remove(&(x, y)) {
+            map.teleporters.retain(|_, other| other.label != t.label);
+        }
+    }
+}
@@ -0,0 +1,48 @@
+// Pistas para llegar a la meta a lo largo del camino más corto: una flecha en el HUD o migas
+// en el piso.
+
+use glam::Vec2;
+
+use crate::Map;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HintMode {
+    #[default]
+    Off,
+    Arrow,
+    Breadcrumbs,
+}
+
+impl HintMode {
+    /// Siguiente modo al presionar la tecla de pistas (apagado → flecha → migas → apagado).
+    pub fn next(self) -> Self {
+        match self {
+            HintMode::Off => HintMode::Arrow,
+            HintMode::Arrow => HintMode::Breadcrumbs,
+            HintMode::Breadcrumbs => HintMode::Off,
+        }
+    }
+}
+
+/// Punto del camino al que apunta la flecha: el centro de celda más lejano que se ve desde
+/// `pos`, para no señalar a través de las paredes. No pasa del primer teletransporte.
+pub fn arrow_target(map: &Map, pos: Vec2, path: &[(i32, i32)]) -> Option<Vec2> {
+    let center = |&(x, y): &(i32, i32)| Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+    // Después de un salto el camino sigue en otra parte del mapa
+    let len = path.windows(2)
+        .position(|w| (w[0].0 - w[1].0).abs() + (w[0].1 - w[1].1).abs() > 1)
+        .map_or(path.len(), |i| i + 1);
+    let path = &path[..len];
+    path.iter()
+        .rev()
+        .map(center)
+        .find(|&c| map.has_line_of_sight(pos, c))
+        .or_else(|| path.get(1).map(center))
+}
+
+/// Ángulo en radianes entre la vista (`dir`) y la dirección de `pos` a `target`; positivo
+/// si el objetivo queda a la derecha.
+pub fn relative_angle(pos: Vec2, dir: Vec2, target: Vec2) -> f32 {
+    let to = target - pos;
+    dir.perp_dot(to).atan2(dir.dot(to))
+}
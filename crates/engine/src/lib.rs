@@ -1,4 +1,8 @@
+pub mod campaign;
+pub mod editor;
+pub mod hint;
 pub mod map;
+pub mod mazegen;
 pub mod player;
 pub mod raycast;
 pub mod textures;
@@ -0,0 +1,60 @@
+// Formato ASCII: cada carácter de la sección `[ascii]` es una celda. La leyenda traduce
+// cada carácter a un token del formato numérico, así ambos formatos comparten el parser.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::is_valid_token;
+
+/// Extensión de los mapas que usan el formato ASCII desde la primera línea.
+pub const ASCII_EXTENSION: &str = "amap";
+
+/// Leyenda carácter -> token. Se puede ampliar o cambiar con una sección `[legend]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Legend(BTreeMap<char, String>);
+
+impl Default for Legend {
+    // `#` pared 1, `a`..`h` paredes 1..8, `.` o espacio piso, `G` meta, `D` puerta,
+    // `S` aparición (o `^ > v <` para indicar la orientación)
+    fn default() -> Self {
+        let mut m: BTreeMap<char, String> = BTreeMap::new();
+        m.insert('#', "1".into());
+        m.insert('.', "0".into());
+        m.insert(' ', "0".into());
+        m.insert('G', "9".into());
+        m.insert('D', "D".into());
+        m.insert('S', "S".into());
+        m.insert('^', "S:N".into());
+        m.insert('>', "S:E".into());
+        m.insert('v', "S:S".into());
+        m.insert('<', "S:W".into());
+        for (i, c) in ('a'..='h').enumerate() {
+            m.insert(c, (i + 1).to_string());
+        }
+        Self(m)
+    }
+}
+
+impl Legend {
+    pub fn get(&self, c: char) -> Option<&str> {
+        self.0.get(&c).map(String::as_str)
+    }
+
+    // Línea `X = token` de la sección `[legend]`; `' '` permite definir el espacio
+    pub(super) fn set(&mut self, key: &str, token: &str) -> Result<(), String> {
+        let key = key.strip_prefix('\'').and_then(|k| k.strip_suffix('\'')).unwrap_or(key);
+        let mut chars = key.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return Err(format!("la leyenda espera un solo carácter: {key}"));
+        };
+        if !is_valid_token(token) {
+            return Err(format!("token inválido en la leyenda: {token}"));
+        }
+        self.0.insert(c, token.to_string());
+        Ok(())
+    }
+}
+
+pub fn is_ascii_path(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|e| e == ASCII_EXTENSION)
+}
@@ -0,0 +1,104 @@
+use glam::Vec2;
+
+use super::{KeyColor, Map};
+use crate::player::Inventory;
+use crate::Player;
+
+// Segundos que tarda una puerta en abrirse o cerrarse por completo
+const DOOR_SLIDE_TIME: f32 = 0.6;
+// Segundos que se mantiene abierta después de activarla
+const DOOR_HOLD: f32 = 3.0;
+// Distancia (en celdas, al centro de la puerta) a la que se abre sola
+const DOOR_NEAR: f32 = 1.2;
+// Fracción abierta a partir de la cual deja pasar al jugador
+const DOOR_PASSABLE: f32 = 0.9;
+// Alcance de la tecla de interactuar
+const DOOR_REACH: f32 = 1.5;
+
+/// Puerta corrediza (token `D`). Se dibuja como una pared delgada en el centro de la celda
+/// que se desliza hacia un lado según `open`. Con `lock` (token `L:<color>`) solo se abre si
+/// el jugador lleva la llave de ese color; después queda sin cerrojo.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Door {
+    pub open: f32, // 0 = cerrada, 1 = abierta
+    pub lock: Option<KeyColor>,
+    hold: f32,
+}
+
+impl Door {
+    pub fn locked(color: KeyColor) -> Self {
+        Self { lock: Some(color), ..Self::default() }
+    }
+
+    pub fn is_passable(&self) -> bool {
+        self.open >= DOOR_PASSABLE
+    }
+
+    // Al recargar el nivel: sigue igual de abierta y, si el jugador ya le había quitado el
+    // cerrojo, sin cerrojo
+    pub(super) fn carry_from(&mut self, old: &Door, inventory: &Inventory) {
+        self.open = old.open;
+        self.hold = old.hold;
+        if old.lock.is_none() && self.lock.is_some_and(|c| inventory.has_key(c)) {
+            self.lock = None;
+        }
+    }
+
+    // Quita el cerrojo si el jugador tiene la llave; `false` si sigue cerrada con llave
+    fn try_unlock(&mut self, player: &Player) -> bool {
+        match self.lock {
+            Some(color) if !player.inventory.has_key(color) => false,
+            _ => {
+                self.lock = None;
+                true
+            }
+        }
+    }
+}
+
+impl Map {
+    pub fn door_at(&self, x: i32, y: i32) -> Option<&Door> {
+        self.doors.get(&(x, y))
+    }
+
+    /// `true` si la hoja de la puerta es vertical (plano x = constante), o sea, si el pasillo
+    /// corre de este a oeste con paredes arriba y abajo.
+    pub fn door_is_vertical(&self, x: i32, y: i32) -> bool {
+        self.is_wall(x, y - 1) && self.is_wall(x, y + 1)
+    }
+
+    /// Abre la puerta que está frente al jugador. Devuelve `true` si había una y se abrió
+    /// (`false` también si le falta la llave).
+    pub fn interact(&mut self, player: &Player) -> bool {
+        let cell = [0.5, 1.0, DOOR_REACH]
+            .into_iter()
+            .map(|d| player.pos + player.dir * d)
+            .map(|p| (p.x.floor() as i32, p.y.floor() as i32))
+            .find(|c| self.doors.contains_key(c));
+        let Some(door) = cell.and_then(|c| self.doors.get_mut(&c)) else { return false };
+        if !door.try_unlock(player) { return false; }
+        door.hold = DOOR_HOLD;
+        true
+    }
+
+    /// Avanza la animación de las puertas. Se abren solas con el jugador cerca y nunca se
+    /// cierran con el jugador dentro. Las que tienen cerrojo esperan a que llegue con la llave.
+    pub fn update_doors(&mut self, dt: f32, player: &Player) {
+        let pos = player.pos;
+        let player_cell = (pos.x.floor() as i32, pos.y.floor() as i32);
+        for (&(x, y), door) in self.doors.iter_mut() {
+            let center = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let near = center.distance(pos) < DOOR_NEAR || player_cell == (x, y);
+            if near && door.try_unlock(player) {
+                door.hold = door.hold.max(DOOR_HOLD * 0.5);
+            }
+            door.hold = (door.hold - dt).max(0.0);
+            let step = dt / DOOR_SLIDE_TIME;
+            door.open = if door.hold > 0.0 {
+                (door.open + step).min(1.0)
+            } else {
+                (door.open - step).max(0.0)
+            };
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::{fmt, io};
+
+/// Posición de un error dentro de un archivo de mapa (línea y columna desde 1).
+/// `line == 0` indica un error que no apunta a una línea concreta.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Location {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { file: None, line, column }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = self.file.as_deref().unwrap_or("<memoria>");
+        if self.line == 0 {
+            write!(f, "{file}")
+        } else {
+            write!(f, "{file}:{}:{}", self.line, self.column)
+        }
+    }
+}
+
+/// Error al leer un mapa.
+#[derive(Debug)]
+pub enum MapError {
+    Io { at: Location, source: io::Error },
+    /// El archivo no contiene ninguna fila.
+    Empty { at: Location },
+    UnknownSection { at: Location, name: String },
+    /// Línea de `[meta]` mal formada (clave desconocida, color inválido...).
+    InvalidMeta { at: Location, message: String },
+    InvalidToken { at: Location, token: String },
+    RaggedRow { at: Location, expected: usize, found: usize },
+    MissingSpawn { at: Location },
+    DuplicateSpawn { at: Location },
+    /// Etiqueta de teletransporte que no aparece exactamente dos veces.
+    UnpairedTeleporter { at: Location, label: String },
+    /// Línea de `[triggers]` mal formada.
+    InvalidTrigger { at: Location, message: String },
+    /// Línea de `[entities]` mal formada.
+    InvalidEntity { at: Location, message: String },
+    /// Capa `[floor]` o `[ceiling]` con otra cantidad de filas que las paredes.
+    LayerSize { at: Location, layer: String, expected: usize, found: usize },
+    /// Archivo de otro editor (Tiled) mal formado o con datos no soportados.
+    Import { at: Location, message: String },
+}
+
+impl MapError {
+    pub fn location(&self) -> &Location {
+        match self {
+            MapError::Io { at, .. }
+            | MapError::Empty { at }
+            | MapError::UnknownSection { at, .. }
+            | MapError::InvalidMeta { at, .. }
+            | MapError::InvalidToken { at, .. }
+            | MapError::RaggedRow { at, .. }
+            | MapError::MissingSpawn { at }
+            | MapError::DuplicateSpawn { at }
+            | MapError::UnpairedTeleporter { at, .. }
+            | MapError::InvalidTrigger { at, .. }
+            | MapError::InvalidEntity { at, .. }
+            | MapError::LayerSize { at, .. }
+            | MapError::Import { at, .. } => at,
+        }
+    }
+
+    // Completa el nombre de archivo (parse_str no lo conoce)
+    pub fn with_file(mut self, file: &str) -> Self {
+        let at = match &mut self {
+            MapError::Io { at, .. }
+            | MapError::Empty { at }
+            | MapError::UnknownSection { at, .. }
+            | MapError::InvalidMeta { at, .. }
+            | MapError::InvalidToken { at, .. }
+            | MapError::RaggedRow { at, .. }
+            | MapError::MissingSpawn { at }
+            | MapError::DuplicateSpawn { at }
+            | MapError::UnpairedTeleporter { at, .. }
+            | MapError::InvalidTrigger { at, .. }
+            | MapError::InvalidEntity { at, .. }
+            | MapError::LayerSize { at, .. }
+            | MapError::Import { at, .. } => at,
+        };
+        at.file = Some(file.to_string());
+        self
+    }
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.location())?;
+        match self {
+            MapError::Io { source, .. } => write!(f, "{source}"),
+            MapError::Empty { .. } => write!(f, "el mapa está vacío"),
+            MapError::UnknownSection { name, .. } => write!(f, "sección desconocida [{name}]"),
+            MapError::InvalidMeta { message, .. } => write!(f, "{message}"),
+            MapError::InvalidToken { token, .. } => write!(f, "token inválido '{token}'"),
+            MapError::RaggedRow { expected, found, .. } => {
+                write!(f, "fila con {found} celdas, se esperaban {expected}")
+            }
+            MapError::MissingSpawn { .. } => write!(f, "el mapa no tiene celda de aparición (S)"),
+            MapError::DuplicateSpawn { .. } => write!(f, "más de una celda de aparición (S)"),
+            MapError::UnpairedTeleporter { label, .. } => {
+                write!(f, "el teletransporte '{label}' debe aparecer exactamente dos veces")
+            }
+            MapError::InvalidTrigger { message, .. } => write!(f, "{message}"),
+            MapError::InvalidEntity { message, .. } => write!(f, "{message}"),
+            MapError::LayerSize { layer, expected, found, .. } => {
+                write!(f, "la capa [{layer}] tiene {found} filas, se esperaban {expected}")
+            }
+            MapError::Import { message, .. } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for MapError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MapError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
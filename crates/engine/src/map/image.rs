@@ -0,0 +1,144 @@
+// Mapas dibujados como imagen PNG: cada píxel es una celda y la paleta traduce su color.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+
+use super::{Door, Facing, LevelMeta, Location, Map, MapError, Rgb, Spawn};
+
+/// Qué representa un color de la imagen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelKind {
+    Floor,
+    Wall(u8),
+    Spawn(Facing),
+    Goal,
+    Door,
+}
+
+/// Paleta color -> celda. Los píxeles totalmente transparentes siempre son piso.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImagePalette(BTreeMap<Rgb, PixelKind>);
+
+impl Default for ImagePalette {
+    // Blanco piso, negro/gris/azul paredes 1/2/3, verde aparición, rojo meta, café puerta
+    fn default() -> Self {
+        Self(BTreeMap::from([
+            ((255, 255, 255), PixelKind::Floor),
+            ((0, 0, 0), PixelKind::Wall(1)),
+            ((128, 128, 128), PixelKind::Wall(2)),
+            ((0, 0, 255), PixelKind::Wall(3)),
+            ((0, 255, 0), PixelKind::Spawn(Facing::East)),
+            ((255, 0, 0), PixelKind::Goal),
+            ((139, 90, 43), PixelKind::Door),
+        ]))
+    }
+}
+
+impl ImagePalette {
+    pub fn empty() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    pub fn insert(&mut self, color: Rgb, kind: PixelKind) {
+        self.0.insert(color, kind);
+    }
+
+    pub fn get(&self, color: Rgb) -> Option<PixelKind> {
+        self.0.get(&color).copied()
+    }
+
+    /// Agrega los colores de pared del nivel, para dibujar con la misma paleta del juego.
+    pub fn with_wall_colors(mut self, meta: &LevelMeta) -> Self {
+        for (&id, &color) in &meta.wall_colors {
+            self.0.entry(color).or_insert(PixelKind::Wall(id));
+        }
+        self
+    }
+}
+
+impl Map {
+    /// Carga un PNG (indexado, gris o RGB/RGBA) usando la paleta dada.
+    pub fn load_from_image(path: &str, palette: &ImagePalette) -> Result<Self, MapError> {
+        let io_err = |source| MapError::Io { at: Location::default(), source }.with_file(path);
+        let file = File::open(path).map_err(io_err)?;
+        let mut decoder = png::Decoder::new(file);
+        // Paleta y profundidades menores a 8 bits se expanden; 16 bits se recortan a 8
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info()
+            .map_err(|e| MapError::Import { at: Location::default(), message: e.to_string() }.with_file(path))?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf)
+            .map_err(|e| MapError::Import { at: Location::default(), message: e.to_string() }.with_file(path))?;
+
+        let (channels, has_alpha) = match info.color_type {
+            png::ColorType::Grayscale => (1, false),
+            png::ColorType::GrayscaleAlpha => (2, true),
+            png::ColorType::Rgb => (3, false),
+            png::ColorType::Rgba => (4, true),
+            png::ColorType::Indexed => unreachable!("EXPAND convierte la paleta a RGB"),
+        };
+        let pixels: Vec<Option<Rgb>> = buf[..info.buffer_size()]
+            .chunks_exact(channels)
+            .map(|p| {
+                if has_alpha && p[channels - 1] == 0 { return None; }
+                Some(if channels < 3 { (p[0], p[0], p[0]) } else { (p[0], p[1], p[2]) })
+            })
+            .collect();
+
+        Self::from_pixels(info.width as i32, info.height as i32, &pixels, palette)
+            .map_err(|e| e.with_file(path))
+    }
+
+    /// Construye el mapa desde píxeles ya decodificados (fila por fila, `None` = transparente).
+    pub fn from_pixels(w: i32, h: i32, pixels: &[Option<Rgb>], palette: &ImagePalette) -> Result<Self, MapError> {
+        if w <= 0 || h <= 0 || pixels.is_empty() {
+            return Err(MapError::Empty { at: Location::default() });
+        }
+        if pixels.len() != (w * h) as usize {
+            return Err(MapError::Import {
+                at: Location::default(),
+                message: format!("{} píxeles para una imagen de {w}x{h}", pixels.len()),
+            });
+        }
+        let mut cells = Vec::with_capacity(pixels.len());
+        let mut goals = BTreeSet::new();
+        let mut doors = BTreeMap::new();
+        let mut spawn = None;
+
+        for (i, &px) in pixels.iter().enumerate() {
+            let (x, y) = (i as i32 % w, i as i32 / w);
+            // Línea = fila de píxeles, columna = x (desde 1)
+            let at = Location::new(y as usize + 1, x as usize + 1);
+            let kind = match px {
+                None => PixelKind::Floor,
+                Some(c) => palette.get(c).ok_or_else(|| MapError::Import {
+                    at: at.clone(),
+                    message: format!("color sin asignar en la paleta: {} {} {}", c.0, c.1, c.2),
+                })?,
+            };
+            cells.push(match kind {
+                PixelKind::Wall(id) => id,
+                PixelKind::Floor => 0,
+                PixelKind::Goal => {
+                    goals.insert((x, y));
+                    0
+                }
+                PixelKind::Spawn(facing) => {
+                    if spawn.is_some() { return Err(MapError::DuplicateSpawn { at }); }
+                    spawn = Some(Spawn { x, y, facing });
+                    0
+                }
+                PixelKind::Door => {
+                    doors.insert((x, y), Door::default());
+                    0
+                }
+            });
+        }
+
+        let spawn = spawn.ok_or(MapError::MissingSpawn { at: Location::default() })?;
+        let mut map = Map::new(w, h, cells, spawn);
+        map.goals = goals;
+        map.doors = doors;
+        Ok(map)
+    }
+}
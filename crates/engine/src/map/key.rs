@@ -0,0 +1,54 @@
+use super::{Map, Rgb};
+
+/// Color de una llave y de las puertas que abre (tokens `K:<color>` y `L:<color>`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KeyColor {
+    Red,
+    Green,
+    Blue,
+    Yellow,
+}
+
+impl KeyColor {
+    pub const ALL: [KeyColor; 4] = [KeyColor::Red, KeyColor::Green, KeyColor::Blue, KeyColor::Yellow];
+
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "red" => Some(KeyColor::Red),
+            "green" => Some(KeyColor::Green),
+            "blue" => Some(KeyColor::Blue),
+            "yellow" => Some(KeyColor::Yellow),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyColor::Red => "red",
+            KeyColor::Green => "green",
+            KeyColor::Blue => "blue",
+            KeyColor::Yellow => "yellow",
+        }
+    }
+
+    // Color con que se dibuja la llave en el HUD, el minimapa y la puerta
+    pub fn rgb(self) -> Rgb {
+        match self {
+            KeyColor::Red => (220, 40, 40),
+            KeyColor::Green => (40, 180, 40),
+            KeyColor::Blue => (40, 80, 220),
+            KeyColor::Yellow => (230, 200, 40),
+        }
+    }
+}
+
+impl Map {
+    pub fn key_at(&self, x: i32, y: i32) -> Option<KeyColor> {
+        self.keys.get(&(x, y)).copied()
+    }
+
+    /// Recoge la llave de la celda (si hay una), quitándola del mapa.
+    pub fn take_key(&mut self, x: i32, y: i32) -> Option<KeyColor> {
+        self.keys.remove(&(x, y))
+    }
+}
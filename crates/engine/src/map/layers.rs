@@ -0,0 +1,60 @@
+// Capas del mapa además de las paredes (`cells`): material del piso y del techo por celda
+// (0 = color por defecto del nivel) y entidades sueltas. En el archivo son las secciones
+// `[floor]` y `[ceiling]` (cuadrículas numéricas del mismo tamaño que `[walls]`/`[map]`) y
+// `[entities]`, una entidad por línea:
+//
+//   [entities]
+//   barrel 3.5 4.5
+//   lamp 2 2 name=entrada color=yellow
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Objeto libre del nivel (sprites, decoración...). Posición en celdas.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entity {
+    pub kind: String,
+    pub name: String,
+    pub x: f32,
+    pub y: f32,
+    pub properties: BTreeMap<String, String>,
+}
+
+impl Entity {
+    // `tipo x y [clave=valor...]`; la propiedad `name` va al nombre
+    pub(super) fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let (Some(kind), Some(x), Some(y)) = (words.next(), words.next(), words.next()) else {
+            return Err(format!("se esperaba 'tipo x y [clave=valor...]': {line}"));
+        };
+        let coord = |v: &str| v.parse::<f32>().map_err(|_| format!("coordenada inválida: {v}"));
+        let mut entity = Entity {
+            kind: kind.to_string(),
+            name: String::new(),
+            x: coord(x)?,
+            y: coord(y)?,
+            properties: BTreeMap::new(),
+        };
+        for word in words {
+            let (key, value) = word.split_once('=').ok_or_else(|| format!("se esperaba 'clave=valor': {word}"))?;
+            if key == "name" {
+                entity.name = value.to_string();
+            } else {
+                entity.properties.insert(key.to_string(), value.to_string());
+            }
+        }
+        Ok(entity)
+    }
+}
+
+// Misma sintaxis que lee `Entity::parse`
+impl fmt::Display for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.kind, self.x, self.y)?;
+        if !self.name.is_empty() { write!(f, " name={}", self.name)?; }
+        for (key, value) in &self.properties {
+            write!(f, " {key}={value}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,499 @@
+pub mod ascii;
+pub mod door;
+pub mod error;
+pub mod image;
+pub mod key;
+pub mod layers;
+pub mod nav;
+pub mod reload;
+pub mod save;
+pub mod teleport;
+pub mod tiled;
+pub mod trigger;
+pub mod validate;
+
+pub use ascii::Legend;
+pub use door::Door;
+pub use error::{Location, MapError};
+pub use image::{ImagePalette, PixelKind};
+pub use key::KeyColor;
+pub use layers::Entity;
+pub use nav::{DistanceField, Neighbors};
+pub use reload::{MapWatcher, ReloadError};
+pub use teleport::Teleporter;
+pub use trigger::{Trigger, TriggerEvent};
+pub use validate::{Diagnostic, Severity};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use glam::Vec2;
+use crate::player::MovementConfig;
+
+pub type Rgb = (u8, u8, u8);
+
+/// Extensiones que `Map::load_from_file` sabe leer.
+pub const LEVEL_EXTENSIONS: &[&str] = &["map", ascii::ASCII_EXTENSION, "tmx", "tmj", "json", "png"];
+
+/// Metadatos opcionales del nivel, leídos del bloque `[meta]` del archivo.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LevelMeta {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub sky: Rgb,
+    pub floor: Rgb,
+    pub wall_colors: BTreeMap<u8, Rgb>, // id de pared -> color
+    pub floor_colors: BTreeMap<u8, Rgb>, // material de piso -> color
+    pub ceiling_colors: BTreeMap<u8, Rgb>, // material de techo -> color
+    pub door: Rgb,
+    pub music: Option<String>,
+    pub movement: MovementConfig,
+}
+
+impl Default for LevelMeta {
+    // Paleta gris para mapas sin encabezado
+    fn default() -> Self {
+        let wall_colors = [(1, (170, 170, 170)), (2, (136, 136, 136)), (3, (102, 102, 102))]
+            .into_iter()
+            .collect();
+        Self {
+            name: None,
+            author: None,
+            sky: (135, 206, 235),
+            floor: (68, 68, 68),
+            wall_colors,
+            floor_colors: BTreeMap::new(),
+            ceiling_colors: BTreeMap::new(),
+            door: (139, 90, 43),
+            music: None,
+            movement: MovementConfig::default(),
+        }
+    }
+}
+
+impl LevelMeta {
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let mut words = key.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some("name"), None, _) => self.name = Some(value.to_string()),
+            (Some("author"), None, _) => self.author = Some(value.to_string()),
+            (Some("music"), None, _) => self.music = Some(value.to_string()),
+            (Some("sky"), None, _) => self.sky = parse_rgb(value)?,
+            (Some("floor"), None, _) => self.floor = parse_rgb(value)?,
+            (Some("door"), None, _) => self.door = parse_rgb(value)?,
+            (Some("move"), Some(name), None) => self.movement.set(name, value)?,
+            (Some("wall"), Some(id), None) => {
+                let id = id.parse::<u8>()
+                    .map_err(|_| format!("id de pared inválido: {id}"))?;
+                self.wall_colors.insert(id, parse_rgb(value)?);
+            }
+            (Some(layer @ ("floor" | "ceiling")), Some(id), None) => {
+                let id = id.parse::<u8>()
+                    .map_err(|_| format!("material inválido: {id}"))?;
+                let colors = if layer == "floor" { &mut self.floor_colors } else { &mut self.ceiling_colors };
+                colors.insert(id, parse_rgb(value)?);
+            }
+            _ => return Err(format!("clave desconocida en [meta]: {key}")),
+        }
+        Ok(())
+    }
+}
+
+// "r g b" (también acepta comas), o "#rrggbb" / "#aarrggbb" como los colores de Tiled
+fn parse_rgb(s: &str) -> Result<Rgb, String> {
+    if let Some(hex) = s.strip_prefix('#') {
+        // Se valida antes de cortar: `&hex[2..]` entra en pánico a mitad de un carácter
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) || !matches!(hex.len(), 6 | 8) {
+            return Err(format!("color inválido: {s}"));
+        }
+        let hex = if hex.len() == 8 { &hex[2..] } else { hex };
+        let v = u32::from_str_radix(hex, 16).map_err(|_| format!("color inválido: {s}"))?;
+        return Ok(((v >> 16) as u8, (v >> 8) as u8, v as u8));
+    }
+    let parts: Vec<u8> = s
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.parse::<u8>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("color inválido: {s}"))?;
+    match parts[..] {
+        [r, g, b] => Ok((r, g, b)),
+        _ => Err(format!("color inválido: {s}")),
+    }
+}
+
+/// Orientación inicial del jugador (N = fila 0, hacia arriba en el mapa).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Facing {
+    North,
+    #[default]
+    East,
+    South,
+    West,
+}
+
+impl Facing {
+    pub fn from_letter(s: &str) -> Option<Self> {
+        match s {
+            "N" => Some(Facing::North),
+            "E" => Some(Facing::East),
+            "S" => Some(Facing::South),
+            "W" => Some(Facing::West),
+            _ => None,
+        }
+    }
+
+    pub fn dir(self) -> Vec2 {
+        match self {
+            Facing::North => Vec2::new(0.0, -1.0),
+            Facing::East => Vec2::new(1.0, 0.0),
+            Facing::South => Vec2::new(0.0, 1.0),
+            Facing::West => Vec2::new(-1.0, 0.0),
+        }
+    }
+}
+
+/// Celda de aparición del jugador: token `S` u `S:<N|E|S|W>` en la cuadrícula.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spawn {
+    pub x: i32,
+    pub y: i32,
+    pub facing: Facing,
+}
+
+impl Spawn {
+    // Centro de la celda
+    pub fn pos(&self) -> Vec2 {
+        Vec2::new(self.x as f32 + 0.5, self.y as f32 + 0.5)
+    }
+}
+
+// Contenido de una celda en el formato numérico
+#[derive(Clone, Copy, PartialEq)]
+enum Token<'a> {
+    Cell(u8), // 0 piso, 9 meta, otro = id de pared
+    Wall(u8), // pared con id explícito, para la pared 9
+    Spawn(Facing),
+    Door,
+    Key(KeyColor),
+    Locked(KeyColor), // puerta que solo abre la llave de ese color
+    Teleport(&'a str, Option<Facing>),
+}
+
+// `S` (aparición mirando al este) o `S:N`, `S:E`, `S:S`, `S:W`; `D` puerta; `K:red` llave;
+// `L:red` puerta con llave; `T:a` o `T:a:N` teletransporte; `W:9` pared con ese id; o un
+// número
+fn parse_token(t: &str) -> Option<Token<'_>> {
+    if let Some(rest) = t.strip_prefix("T:") {
+        let (label, facing) = match rest.split_once(':') {
+            Some((label, f)) => (label, Some(Facing::from_letter(f)?)),
+            None => (rest, None),
+        };
+        return teleport::is_valid_label(label).then_some(Token::Teleport(label, facing));
+    }
+    match t.split_once(':') {
+        None if t == "S" => Some(Token::Spawn(Facing::default())),
+        None if t == "D" => Some(Token::Door),
+        Some(("S", f)) => Facing::from_letter(f).map(Token::Spawn),
+        Some(("K", c)) => KeyColor::from_name(c).map(Token::Key),
+        Some(("L", c)) => KeyColor::from_name(c).map(Token::Locked),
+        Some(("W", id)) => id.parse::<u8>().ok().filter(|&id| id != 0).map(Token::Wall),
+        _ => t.parse::<u8>().ok().map(Token::Cell),
+    }
+}
+
+fn is_valid_token(t: &str) -> bool {
+    parse_token(t).is_some()
+}
+
+// Sección del archivo que se está leyendo
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Meta,
+    Legend,
+    Grid,  // formato numérico
+    Ascii, // un carácter por celda
+    Floor,
+    Ceiling,
+    Entities,
+    Triggers,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Map {
+    pub w: i32,
+    pub h: i32,
+    pub cells: Vec<u8>, // capa de paredes: 0 = vacío, >0 = id de pared
+    pub floor: Vec<u8>, // material del piso por celda (0 = color del nivel)
+    pub ceiling: Vec<u8>, // material del techo por celda (0 = cielo del nivel)
+    pub goals: BTreeSet<(i32,i32)>, // celdas de victoria (token 9)
+    pub doors: BTreeMap<(i32,i32), Door>, // celdas con puerta (vacías en `cells`)
+    pub keys: BTreeMap<(i32,i32), KeyColor>, // llaves sin recoger
+    pub teleporters: BTreeMap<(i32,i32), Teleporter>, // en pares con la misma etiqueta
+    pub triggers: Vec<Trigger>,
+    pub entities: Vec<Entity>,
+    pub spawn: Spawn,
+    pub meta: LevelMeta,
+}
+
+impl Map {
+    /// Mapa con solo la capa de paredes: sin metas, puertas, llaves, teletransportes ni
+    /// entidades, con piso y techo por defecto y los metadatos por defecto.
+    pub fn new(w: i32, h: i32, cells: Vec<u8>, spawn: Spawn) -> Self {
+        let size = (w * h).max(0) as usize;
+        Self {
+            w,
+            h,
+            cells,
+            floor: vec![0; size],
+            ceiling: vec![0; size],
+            goals: BTreeSet::new(),
+            doors: BTreeMap::new(),
+            keys: BTreeMap::new(),
+            teleporters: BTreeMap::new(),
+            triggers: Vec::new(),
+            entities: Vec::new(),
+            spawn,
+            meta: LevelMeta::default(),
+        }
+    }
+
+    pub fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x >= self.w || y >= self.h { None }
+        else { Some((y * self.w + x) as usize) }
+    }
+
+    // Pared o puerta sin abrir
+    pub fn is_solid(&self, x: i32, y: i32) -> bool {
+        self.is_wall(x, y) || self.door_at(x, y).is_some_and(|d| !d.is_passable())
+    }
+
+    // Solo paredes fijas (las puertas cuentan como pasables)
+    pub fn is_wall(&self, x: i32, y: i32) -> bool {
+        self.index(x,y).map(|i| self.cells[i] > 0).unwrap_or(true)
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> u8 {
+        self.index(x,y).map(|i| self.cells[i]).unwrap_or(255)
+    }
+
+    pub fn is_goal(&self, x: i32, y: i32) -> bool {
+        self.goals.contains(&(x, y))
+    }
+
+    // Formato simple: números separados por espacios, cada línea = fila; `S` marca la aparición,
+    // `D` una puerta, `K:<color>` una llave, `L:<color>` una puerta con llave y `T:<etiqueta>`
+    // un teletransporte.
+    // Opcionalmente empieza con un bloque `[meta]` (clave = valor) seguido de `[map]` (o
+    // `[walls]`), y puede seguir con las capas `[floor]`, `[ceiling]` y `[entities]` (ver
+    // `map::layers`) y con `[triggers]` (ver `map::trigger`).
+    // La cuadrícula también puede escribirse en ASCII (sección `[ascii]`, ver `map::ascii`);
+    // los archivos `.amap` usan ASCII desde el inicio, los de Tiled se importan
+    // y los `.png` se leen con la paleta por defecto.
+    pub fn load_from_file(path: &str) -> Result<Self, MapError> {
+        if tiled::is_tiled_path(path) {
+            return Self::load_tiled(path);
+        }
+        if path.ends_with(".png") {
+            return Self::load_from_image(path, &ImagePalette::default());
+        }
+        let src = fs::read_to_string(path)
+            .map_err(|source| MapError::Io { at: Location::default(), source }.with_file(path))?;
+        let parsed = if ascii::is_ascii_path(path) { Self::parse_ascii_str(&src) } else { Self::parse_str(&src) };
+        parsed.map_err(|e| e.with_file(path))
+    }
+
+    pub fn parse_str(src: &str) -> Result<Self, MapError> {
+        Self::parse(src, Section::Grid)
+    }
+
+    /// Como `parse_str`, pero la cuadrícula sin encabezado se lee en formato ASCII.
+    pub fn parse_ascii_str(src: &str) -> Result<Self, MapError> {
+        Self::parse(src, Section::Ascii)
+    }
+
+    fn parse(src: &str, grid: Section) -> Result<Self, MapError> {
+    let mut cells: Vec<u8> = Vec::new();
+    let mut w = 0i32;
+    let mut h = 0i32;
+    let mut goals = BTreeSet::new();
+    let mut doors = BTreeMap::new();
+    let mut keys = BTreeMap::new();
+    let mut teleporters = BTreeMap::new();
+    let mut teleporter_at = BTreeMap::new(); // posición en el archivo, para los errores
+    let mut triggers = Vec::new();
+    let mut floor_rows = Vec::new();
+    let mut ceiling_rows = Vec::new();
+    let mut entities = Vec::new();
+    let mut spawn: Option<Spawn> = None;
+    let mut meta = LevelMeta::default();
+    let mut legend = Legend::default();
+    let mut section = grid;
+
+    for (n, raw) in src.lines().enumerate() {
+        let line_no = n + 1;
+        let line = raw.trim();
+        // En ASCII los espacios son celdas: sólo se saltan las líneas vacías de verdad
+        let ascii = section == Section::Ascii;
+        let raw = if ascii { raw.trim_end_matches('\r') } else { raw };
+        if (ascii && raw.is_empty()) || (!ascii && line.is_empty()) { continue; }
+        let indent = raw.len() - raw.trim_start().len();
+        let at = |col: usize| Location::new(line_no, col + 1);
+
+        match line {
+            "[meta]" if h == 0 => { section = Section::Meta; continue; }
+            "[legend]" if h == 0 => { section = Section::Legend; continue; }
+            "[map]" | "[walls]" => { section = Section::Grid; continue; }
+            "[ascii]" => { section = Section::Ascii; continue; }
+            "[floor]" => { section = Section::Floor; continue; }
+            "[ceiling]" => { section = Section::Ceiling; continue; }
+            "[entities]" => { section = Section::Entities; continue; }
+            "[triggers]" => { section = Section::Triggers; continue; }
+            _ if line.starts_with('[') && line.ends_with(']') => {
+                let name = line[1..line.len() - 1].to_string();
+                return Err(MapError::UnknownSection { at: at(indent), name });
+            }
+            _ => {}
+        }
+
+        if matches!(section, Section::Floor | Section::Ceiling) {
+            let row = tokens(raw)
+                .map(|(col, t)| t.parse::<u8>().map_err(|_| MapError::InvalidToken { at: at(col), token: t.to_string() }))
+                .collect::<Result<Vec<_>, _>>()?;
+            let rows = if section == Section::Floor { &mut floor_rows } else { &mut ceiling_rows };
+            rows.push((at(indent), row));
+            continue;
+        }
+
+        if section == Section::Entities {
+            if line.starts_with('#') { continue; }
+            let entity = Entity::parse(line)
+                .map_err(|message| MapError::InvalidEntity { at: at(indent), message })?;
+            entities.push(entity);
+            continue;
+        }
+
+        if section == Section::Triggers {
+            if line.starts_with('#') { continue; }
+            let trigger = Trigger::parse(line)
+                .map_err(|message| MapError::InvalidTrigger { at: at(indent), message })?;
+            triggers.push(trigger);
+            continue;
+        }
+
+        if matches!(section, Section::Meta | Section::Legend) {
+            if section == Section::Meta && line.starts_with('#') { continue; }
+            // En la leyenda la clave puede ser el propio '=', así que se separa por el último
+            let split = if section == Section::Meta { line.split_once('=') } else { line.rsplit_once('=') };
+            let (key, value) = split.ok_or_else(|| MapError::InvalidMeta {
+                at: at(indent),
+                message: format!("se esperaba 'clave = valor': {line}"),
+            })?;
+            let result = if section == Section::Meta {
+                meta.set(key.trim(), value.trim())
+            } else {
+                legend.set(key.trim(), value.trim())
+            };
+            result.map_err(|message| MapError::InvalidMeta { at: at(indent), message })?;
+            continue;
+        }
+
+        let row_tokens: Vec<(usize, &str)> = if section == Section::Ascii {
+            raw.char_indices()
+                .map(|(col, c)| {
+                    let t = legend.get(c)
+                        .ok_or_else(|| MapError::InvalidToken { at: at(col), token: c.to_string() })?;
+                    Ok((col, t))
+                })
+                .collect::<Result<_, _>>()?
+        } else {
+            tokens(raw).collect()
+        };
+
+        let row = row_tokens.iter()
+            .map(|&(col, t)| {
+                let token = parse_token(t)
+                    .ok_or_else(|| MapError::InvalidToken { at: at(col), token: t.to_string() })?;
+                Ok((col, token))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if w == 0 { w = row.len() as i32; }
+        if row.len() as i32 != w {
+            // Columna del primer token sobrante, o fin de línea si faltan
+            let col = row_tokens.get(w as usize).map(|&(c, _)| c).unwrap_or(if ascii { raw.len() } else { raw.trim_end().len() });
+            return Err(MapError::RaggedRow { at: at(col), expected: w as usize, found: row.len() });
+        }
+
+        for (x, &(col, token)) in row.iter().enumerate() {
+            let x = x as i32;
+            cells.push(match token {
+                Token::Cell(9) => {
+                    goals.insert((x, h));
+                    0 // la meta no es sólida
+                }
+                Token::Cell(id) | Token::Wall(id) => id,
+                Token::Spawn(facing) => {
+                    if spawn.is_some() { return Err(MapError::DuplicateSpawn { at: at(col) }); }
+                    spawn = Some(Spawn { x, y: h, facing });
+                    0
+                }
+                Token::Door => {
+                    doors.insert((x, h), Door::default());
+                    0
+                }
+                Token::Locked(color) => {
+                    doors.insert((x, h), Door::locked(color));
+                    0
+                }
+                Token::Key(color) => {
+                    keys.insert((x, h), color);
+                    0
+                }
+                Token::Teleport(label, facing) => {
+                    teleporters.insert((x, h), Teleporter::new(label, facing));
+                    teleporter_at.insert((x, h), at(col));
+                    0
+                }
+            });
+        }
+        h += 1;
+    }
+
+    if h == 0 { return Err(MapError::Empty { at: Location::default() }); }
+    let spawn = spawn.ok_or(MapError::MissingSpawn { at: Location::default() })?;
+    if let Some((cell, label)) = teleport::unpaired(&teleporters) {
+        return Err(MapError::UnpairedTeleporter { at: teleporter_at[&cell].clone(), label: label.to_string() });
+    }
+    let floor = material_layer(floor_rows, w, h, "floor")?;
+    let ceiling = material_layer(ceiling_rows, w, h, "ceiling")?;
+    let mut map = Map::new(w, h, cells, spawn);
+    map.floor = floor;
+    map.ceiling = ceiling;
+    map.goals = goals;
+    map.doors = doors;
+    map.keys = keys;
+    map.teleporters = teleporters;
+    map.triggers = triggers;
+    map.entities = entities;
+    map.meta = meta;
+    Ok(map)
+}
+
+}
+
+// Capa `[floor]` o `[ceiling]`: sin filas es todo 0, si no debe medir lo mismo que las paredes
+fn material_layer(rows: Vec<(Location, Vec<u8>)>, w: i32, h: i32, layer: &str) -> Result<Vec<u8>, MapError> {
+    if rows.is_empty() { return Ok(vec![0; (w * h) as usize]); }
+    if let Some((at, row)) = rows.iter().find(|(_, row)| row.len() != w as usize) {
+        return Err(MapError::RaggedRow { at: at.clone(), expected: w as usize, found: row.len() });
+    }
+    if rows.len() != h as usize {
+        return Err(MapError::LayerSize { at: rows[0].0.clone(), layer: layer.to_string(), expected: h as usize, found: rows.len() });
+    }
+    Ok(rows.into_iter().flat_map(|(_, row)| row).collect())
+}
+
+// Tokens separados por espacios junto con su columna (en bytes, desde 0)
+fn tokens(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split_whitespace()
+        .map(move |t| (t.as_ptr() as usize - line.as_ptr() as usize, t))
+}
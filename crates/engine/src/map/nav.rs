@@ -0,0 +1,270 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use glam::Vec2;
+
+use super::Map;
+use crate::player::Inventory;
+use crate::raycast::Dda;
+
+// Costo de un paso recto y de uno diagonal (≈ 10·√2), enteros para ordenar el montículo
+const STRAIGHT: u32 = 10;
+const DIAGONAL: u32 = 14;
+
+const ORTHOGONAL: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const DIAGONALS: [(i32, i32); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+/// Vecinos que se consideran al buscar caminos.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighbors {
+    Four,
+    /// También en diagonal, sin cortar esquinas: las dos celdas rectas del paso tienen que
+    /// estar libres.
+    Eight,
+}
+
+/// Distancia en pasos rectos desde un conjunto de celdas de origen hasta cada celda
+/// alcanzable caminando. Entrar a un teletransporte cuenta como un paso hasta su pareja.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistanceField {
+    w: i32,
+    h: i32,
+    dist: Vec<Option<u32>>,
+    toward: Vec<Option<(i32, i32)>>, // celda a la que se camina para acercarse
+}
+
+impl DistanceField {
+    pub fn get(&self, x: i32, y: i32) -> Option<u32> {
+        if x < 0 || y < 0 || x >= self.w || y >= self.h { return None; }
+        self.dist[(y * self.w + x) as usize]
+    }
+
+    /// Distancia de la celda alcanzable más lejana.
+    pub fn max(&self) -> Option<u32> {
+        self.dist.iter().flatten().copied().max()
+    }
+
+    /// Vecino al que caminar para acercarse un paso a los orígenes; si es un teletransporte
+    /// se sigue desde su pareja. `None` en un origen o fuera de alcance.
+    pub fn next_step(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        self.get(x, y)?;
+        self.toward[(y * self.w + x) as usize]
+    }
+}
+
+impl Map {
+    /// Celda por la que se puede caminar: sin pared ni puerta con cerrojo. Las demás puertas
+    /// cuentan como libres porque se abren al acercarse. Los teletransportes no se siguen.
+    pub fn is_walkable(&self, x: i32, y: i32) -> bool {
+        !self.is_wall(x, y) && self.door_at(x, y).is_none_or(|d| d.lock.is_none())
+    }
+
+    /// Camino más corto (A*) de `from` a `to` sobre las celdas caminables, con ambos extremos
+    /// incluidos. `None` si alguno de los dos no es caminable o no hay camino.
+    pub fn find_path(&self, from: (i32, i32), to: (i32, i32), neighbors: Neighbors) -> Option<Vec<(i32, i32)>> {
+        if !self.is_walkable(from.0, from.1) || !self.is_walkable(to.0, to.1) { return None; }
+        let heuristic = |(x, y): (i32, i32)| {
+            let (dx, dy) = ((x - to.0).unsigned_abs(), (y - to.1).unsigned_abs());
+            match neighbors {
+                Neighbors::Four => STRAIGHT * (dx + dy),
+                Neighbors::Eight => STRAIGHT * dx.max(dy) + (DIAGONAL - STRAIGHT) * dx.min(dy),
+            }
+        };
+
+        let size = self.cells.len();
+        let mut cost = vec![u32::MAX; size];
+        let mut came_from: Vec<Option<usize>> = vec![None; size];
+        let mut open = BinaryHeap::new();
+        let start = self.index(from.0, from.1)?;
+        cost[start] = 0;
+        open.push(Reverse((heuristic(from), start)));
+
+        while let Some(Reverse((_, i))) = open.pop() {
+            let cell = (i as i32 % self.w, i as i32 / self.w);
+            if cell == to {
+                let mut path = vec![cell];
+                let mut at = i;
+                while let Some(prev) = came_from[at] {
+                    path.push((prev as i32 % self.w, prev as i32 / self.w));
+                    at = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for (next, step) in self.nav_neighbors(cell, neighbors) {
+                let Some(j) = self.index(next.0, next.1) else { continue };
+                let g = cost[i] + step;
+                if g < cost[j] {
+                    cost[j] = g;
+                    came_from[j] = Some(i);
+                    open.push(Reverse((g + heuristic(next), j)));
+                }
+            }
+        }
+        None
+    }
+
+    /// Distancias (BFS, cuatro vecinos) desde las celdas `sources` para quien lleva
+    /// `inventory`: las puertas con cerrojo cuentan como libres si tiene la llave. Pisar un
+    /// teletransporte lleva siempre a su pareja, igual que en el juego.
+    pub fn distance_field(&self, sources: impl IntoIterator<Item = (i32, i32)>, inventory: &Inventory) -> DistanceField {
+        let passable = |x: i32, y: i32| {
+            !self.is_wall(x, y) && self.door_at(x, y).is_none_or(|d| d.lock.is_none_or(|c| inventory.has_key(c)))
+        };
+        let mut dist = vec![None; self.cells.len()];
+        let mut toward = vec![None; self.cells.len()];
+        let mut queue = VecDeque::new();
+        for (x, y) in sources {
+            if !passable(x, y) { continue; }
+            if let Some(i) = self.index(x, y) {
+                dist[i] = Some(0);
+                queue.push_back((x, y, 0));
+            }
+        }
+        while let Some((x, y, d)) = queue.pop_front() {
+            // A la llegada de un teletransporte no se entra caminando sino por su pareja
+            let entry = self.teleport_target(x, y).map_or((x, y), |(cell, _)| cell);
+            for (dx, dy) in ORTHOGONAL {
+                let (nx, ny) = (entry.0 + dx, entry.1 + dy);
+                if !passable(nx, ny) { continue; }
+                let Some(i) = self.index(nx, ny) else { continue };
+                if dist[i].is_none() {
+                    dist[i] = Some(d + 1);
+                    toward[i] = Some(entry);
+                    queue.push_back((nx, ny, d + 1));
+                }
+            }
+        }
+        DistanceField { w: self.w, h: self.h, dist, toward }
+    }
+
+    /// Distancia de cada celda a la meta más cercana.
+    pub fn goal_distances(&self, inventory: &Inventory) -> DistanceField {
+        self.distance_field(self.goals.iter().copied(), inventory)
+    }
+
+    /// Camino más corto (cuatro vecinos) de `from` a la meta más cercana, con ambos extremos.
+    /// Al pasar por un teletransporte incluye la celda de entrada y la de llegada.
+    pub fn path_to_goal(&self, from: (i32, i32), inventory: &Inventory) -> Option<Vec<(i32, i32)>> {
+        let field = self.goal_distances(inventory);
+        field.get(from.0, from.1)?;
+        let mut path = vec![from];
+        let mut at = from;
+        while let Some(next) = field.next_step(at.0, at.1) {
+            path.push(next);
+            at = next;
+            if let Some((arrival, _)) = self.teleport_target(next.0, next.1) {
+                path.push(arrival);
+                at = arrival;
+            }
+        }
+        Some(path)
+    }
+
+    /// `true` si el segmento de `a` a `b` no cruza paredes ni puertas cerradas. Recorre las
+    /// celdas con el mismo DDA que los rayos de `cast_frame`.
+    pub fn has_line_of_sight(&self, a: Vec2, b: Vec2) -> bool {
+        let mut dda = Dda::new(a, b - a);
+        // `b - a` mide 1 en unidades del rayo: pasado 1 ya se llegó a `b`
+        while dda.next_t() < 1.0 {
+            dda.advance();
+            if self.is_solid(dda.cell.0, dda.cell.1) { return false; }
+        }
+        true
+    }
+
+    // Vecinos caminables de una celda con el costo del paso
+    fn nav_neighbors(&self, (x, y): (i32, i32), neighbors: Neighbors) -> Vec<((i32, i32), u32)> {
+        let mut out: Vec<_> = ORTHOGONAL.iter()
+            .map(|&(dx, dy)| ((x + dx, y + dy), STRAIGHT))
+            .filter(|&((nx, ny), _)| self.is_walkable(nx, ny))
+            .collect();
+        if neighbors == Neighbors::Eight {
+            out.extend(DIAGONALS.iter()
+                .filter(|&&(dx, dy)| self.is_walkable(x + dx, y) && self.is_walkable(x, y + dy))
+                .map(|&(dx, dy)| ((x + dx, y + dy), DIAGONAL))
+                .filter(|&((nx, ny), _)| self.is_walkable(nx, ny)));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::KeyColor;
+
+    fn map(src: &str) -> Map {
+        Map::parse_str(src).unwrap()
+    }
+
+    const OPEN: &str = "1 1 1 1 1\n1 S 0 0 1\n1 0 0 0 1\n1 0 0 9 1\n1 1 1 1 1\n";
+
+    #[test]
+    fn four_neighbors_walk_around_walls() {
+        let m = map("1 1 1 1 1\n1 S 1 9 1\n1 0 0 0 1\n1 1 1 1 1\n");
+        assert_eq!(
+            m.find_path((1, 1), (3, 1), Neighbors::Four),
+            Some(vec![(1, 1), (1, 2), (2, 2), (3, 2), (3, 1)]),
+        );
+        assert_eq!(m.find_path((1, 1), (2, 1), Neighbors::Four), None);
+    }
+
+    #[test]
+    fn eight_neighbors_cut_diagonally() {
+        let m = map(OPEN);
+        assert_eq!(m.find_path((1, 1), (3, 3), Neighbors::Eight), Some(vec![(1, 1), (2, 2), (3, 3)]));
+    }
+
+    #[test]
+    fn diagonal_does_not_cut_corners() {
+        // Con una sola de las celdas rectas libre se rodea la esquina
+        let m = map("1 1 1 1\n1 S 1 1\n1 0 9 1\n1 1 1 1\n");
+        assert_eq!(m.find_path((1, 1), (2, 2), Neighbors::Eight), Some(vec![(1, 1), (1, 2), (2, 2)]));
+        // Con las dos tapadas no hay paso
+        let m = map("1 1 1 1\n1 S 1 1\n1 1 9 1\n1 1 1 1\n");
+        assert_eq!(m.find_path((1, 1), (2, 2), Neighbors::Eight), None);
+    }
+
+    #[test]
+    fn distance_field_counts_straight_steps() {
+        let m = map(OPEN);
+        let field = m.goal_distances(&Inventory::default());
+        assert_eq!(field.get(3, 3), Some(0));
+        assert_eq!(field.get(1, 1), Some(4));
+        assert_eq!(field.get(0, 0), None);
+        assert_eq!(field.max(), Some(4));
+        assert_eq!(m.path_to_goal((1, 1), &Inventory::default()).map(|p| p.len()), Some(5));
+    }
+
+    #[test]
+    fn locked_door_opens_with_key() {
+        let m = map("1 1 1 1 1\n1 S L:red 9 1\n1 1 1 1 1\n");
+        let mut inventory = Inventory::default();
+        assert_eq!(m.path_to_goal((1, 1), &inventory), None);
+        inventory.add_key(KeyColor::Red);
+        assert_eq!(m.path_to_goal((1, 1), &inventory), Some(vec![(1, 1), (2, 1), (3, 1)]));
+    }
+
+    #[test]
+    fn path_goes_through_teleporter() {
+        let m = map("1 1 1 1 1\n1 S T:a 1 1\n1 1 1 1 1\n1 9 0 T:a 1\n1 1 1 1 1\n");
+        assert_eq!(
+            m.path_to_goal((1, 1), &Inventory::default()),
+            Some(vec![(1, 1), (2, 1), (3, 3), (2, 3), (1, 3)]),
+        );
+    }
+
+    #[test]
+    fn line_of_sight() {
+        let mut m = map("1 1 1 1 1\n1 S D 9 1\n1 0 1 0 1\n1 1 1 1 1\n");
+        let (a, b) = (Vec2::new(1.5, 1.5), Vec2::new(3.5, 1.5));
+        assert!(m.has_line_of_sight(a, Vec2::new(1.5, 2.5)));
+        // La puerta cerrada tapa, abierta no
+        assert!(!m.has_line_of_sight(a, b));
+        m.doors.get_mut(&(2, 1)).unwrap().open = 1.0;
+        assert!(m.has_line_of_sight(a, b));
+        // La pared entre las dos celdas de abajo
+        assert!(!m.has_line_of_sight(Vec2::new(1.5, 2.5), Vec2::new(3.5, 2.5)));
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt;
+use std::fs;
+use std::time::{Duration, Instant, SystemTime};
+
+use super::{Diagnostic, Map, MapError};
+use super::trigger::Action;
+use crate::player::Inventory;
+
+// Cada cuánto se revisa la fecha de modificación del archivo
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Por qué no se pudo recargar un nivel.
+#[derive(Debug)]
+pub enum ReloadError {
+    Parse(MapError),
+    /// El archivo carga pero tiene errores de validación (solo los de severidad `Error`).
+    Invalid(Vec<Diagnostic>),
+}
+
+impl fmt::Display for ReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReloadError::Parse(e) => write!(f, "{e}"),
+            ReloadError::Invalid(diagnostics) => {
+                for (i, d) in diagnostics.iter().enumerate() {
+                    if i > 0 { writeln!(f)?; }
+                    write!(f, "{d}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Vigila el archivo de un nivel para recargarlo mientras se juega. No usa notificaciones
+/// del sistema: compara la fecha de modificación como mucho dos veces por segundo.
+pub struct MapWatcher {
+    path: String,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+}
+
+impl MapWatcher {
+    pub fn new(path: &str) -> Self {
+        Self { path: path.to_string(), modified: modified(path), last_check: Instant::now() }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// `Some` si el archivo cambió desde la última revisión: el mapa nuevo, o el error de
+    /// formato o de validación que impide usarlo.
+    pub fn poll(&mut self) -> Option<Result<Map, ReloadError>> {
+        if self.last_check.elapsed() < POLL_INTERVAL { return None; }
+        self.last_check = Instant::now();
+        let now = modified(&self.path);
+        if now == self.modified { return None; }
+        self.modified = now;
+
+        Some(Map::load_from_file(&self.path)
+            .map_err(ReloadError::Parse)
+            .and_then(|map| {
+                let errors: Vec<Diagnostic> = map.validate().into_iter().filter(Diagnostic::is_error).collect();
+                if errors.is_empty() { Ok(map) } else { Err(ReloadError::Invalid(errors)) }
+            }))
+    }
+}
+
+impl Map {
+    /// Pasa al mapa recién recargado lo que ya ocurrió en `old`: los disparadores iguales en
+    /// la misma posición de la lista siguen disparados (y sus cambios de celda aplicados).
+    /// Si el tamaño no cambió, también las llaves ya recogidas, el estado de las puertas y los
+    /// teletransportes descubiertos.
+    pub fn carry_state_from(&mut self, old: &Map, inventory: &Inventory) {
+        let mut changes = Vec::new();
+        for (trigger, before) in self.triggers.iter_mut().zip(&old.triggers) {
+            if !before.fired || trigger.when != before.when || trigger.actions != before.actions { continue; }
+            trigger.fired = true;
+            changes.extend(trigger.actions.iter().filter_map(|a| match *a {
+                Action::SetCell { x, y, id } => Some((x, y, id)),
+                _ => None,
+            }));
+        }
+        for (x, y, id) in changes {
+            if let Some(i) = self.index(x, y) {
+                self.cells[i] = id;
+            }
+        }
+
+        if (self.w, self.h) != (old.w, old.h) { return; }
+        // Una llave que ya no está y cuyo color lleva el jugador fue recogida
+        self.keys.retain(|cell, &mut color| old.keys.contains_key(cell) || !inventory.has_key(color));
+        for (cell, door) in self.doors.iter_mut() {
+            if let Some(before) = old.doors.get(cell) {
+                door.carry_from(before, inventory);
+            }
+        }
+        for (cell, t) in self.teleporters.iter_mut() {
+            t.discovered |= old.teleporters.get(cell).is_some_and(|o| o.discovered && o.label == t.label);
+        }
+    }
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
@@ -0,0 +1,173 @@
+use std::{fmt, fs, io};
+
+use super::{Facing, Map};
+use crate::player::MovementConfig;
+
+impl Facing {
+    pub fn letter(self) -> char {
+        match self {
+            Facing::North => 'N',
+            Facing::East => 'E',
+            Facing::South => 'S',
+            Facing::West => 'W',
+        }
+    }
+}
+
+// Escribe el mismo formato que lee `Map::parse_str`, así que cargar -> guardar -> cargar
+// da un mapa idéntico. La aparición tiene prioridad si coincide con una meta, y la pared 9
+// se escribe `W:9` porque el número 9 es la meta.
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let meta = &self.meta;
+        writeln!(f, "[meta]")?;
+        if let Some(name) = &meta.name { writeln!(f, "name = {name}")?; }
+        if let Some(author) = &meta.author { writeln!(f, "author = {author}")?; }
+        let (r, g, b) = meta.sky;
+        writeln!(f, "sky = {r} {g} {b}")?;
+        let (r, g, b) = meta.floor;
+        writeln!(f, "floor = {r} {g} {b}")?;
+        for (id, (r, g, b)) in &meta.wall_colors {
+            writeln!(f, "wall {id} = {r} {g} {b}")?;
+        }
+        for (id, (r, g, b)) in &meta.floor_colors {
+            writeln!(f, "floor {id} = {r} {g} {b}")?;
+        }
+        for (id, (r, g, b)) in &meta.ceiling_colors {
+            writeln!(f, "ceiling {id} = {r} {g} {b}")?;
+        }
+        let (r, g, b) = meta.door;
+        writeln!(f, "door = {r} {g} {b}")?;
+        if let Some(music) = &meta.music { writeln!(f, "music = {music}")?; }
+        let default_movement = MovementConfig::default().params();
+        for ((name, value), (_, default)) in meta.movement.params().into_iter().zip(default_movement) {
+            if value != default { writeln!(f, "move {name} = {value}")?; }
+        }
+
+        writeln!(f)?;
+        writeln!(f, "[map]")?;
+        for y in 0..self.h {
+            for x in 0..self.w {
+                if x > 0 { write!(f, " ")?; }
+                if (x, y) == (self.spawn.x, self.spawn.y) {
+                    match self.spawn.facing {
+                        Facing::East => write!(f, "S")?,
+                        facing => write!(f, "S:{}", facing.letter())?,
+                    }
+                } else if self.is_goal(x, y) {
+                    write!(f, "9")?;
+                } else if let Some(door) = self.door_at(x, y) {
+                    match door.lock {
+                        Some(color) => write!(f, "L:{}", color.name())?,
+                        None => write!(f, "D")?,
+                    }
+                } else if let Some(color) = self.key_at(x, y) {
+                    write!(f, "K:{}", color.name())?;
+                } else if let Some(t) = self.teleporter_at(x, y) {
+                    match t.facing {
+                        Some(facing) => write!(f, "T:{}:{}", t.label, facing.letter())?,
+                        None => write!(f, "T:{}", t.label)?,
+                    }
+                } else {
+                    match self.get(x, y) {
+                        9 => write!(f, "W:9")?,
+                        id => write!(f, "{id}")?,
+                    }
+                }
+            }
+            writeln!(f)?;
+        }
+        for (name, layer) in [("floor", &self.floor), ("ceiling", &self.ceiling)] {
+            if layer.iter().all(|&m| m == 0) { continue; }
+            writeln!(f)?;
+            writeln!(f, "[{name}]")?;
+            for row in layer.chunks(self.w as usize) {
+                let row: Vec<String> = row.iter().map(u8::to_string).collect();
+                writeln!(f, "{}", row.join(" "))?;
+            }
+        }
+        if !self.entities.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "[entities]")?;
+            for entity in &self.entities {
+                writeln!(f, "{entity}")?;
+            }
+        }
+        if !self.triggers.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "[triggers]")?;
+            for trigger in &self.triggers {
+                writeln!(f, "{trigger}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Map {
+    pub fn save_to_file(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Un poco de todo lo que el formato sabe escribir
+    const FULL: &str = "\
+[meta]
+name = Todo junto
+author = Pruebas
+sky = 10 20 30
+wall 2 = 200 100 50
+floor 1 = 90 90 90
+music = assets/music/test.ogg
+move speed = 3
+move stamina = 5.5
+
+[map]
+1 1 1 1 1 1 1
+1 S:N 0 D L:red 9 1
+1 K:red T:a W:9 T:a:W 0 1
+1 1 1 1 1 1 1
+
+[floor]
+0 0 0 0 0 0 0
+0 1 1 1 1 1 0
+0 1 1 1 1 1 0
+0 0 0 0 0 0 0
+
+[ceiling]
+0 0 0 0 0 0 0
+0 0 3 3 0 0 0
+0 0 3 3 0 0 0
+0 0 0 0 0 0 0
+
+[entities]
+lamp 2.5 1.5 name=farol light=warm
+
+[triggers]
+enter 1 2 -> open 3 1; message Hola
+pickup red -> wall 5 2 2
+after 30 -> end
+";
+
+    #[test]
+    fn round_trips_every_feature() {
+        let map = Map::parse_str(FULL).unwrap();
+        assert_eq!(map.goals.len(), 1);
+        assert_eq!(map.get(3, 2), 9); // pared 9, no meta
+        assert_eq!(map.doors.len(), 2);
+        assert_eq!(map.keys.len(), 1);
+        assert_eq!(map.teleporters.len(), 2);
+        assert_eq!(map.triggers.len(), 3);
+        assert_eq!(map.entities.len(), 1);
+        assert_ne!(map.meta.movement, MovementConfig::default());
+
+        let saved = map.to_string();
+        assert_eq!(Map::parse_str(&saved).unwrap(), map, "guardado:\n{saved}");
+        // Guardar otra vez lo ya guardado no cambia nada
+        assert_eq!(Map::parse_str(&saved).unwrap().to_string(), saved);
+    }
+}
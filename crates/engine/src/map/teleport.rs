@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+
+use super::{Facing, Map};
+
+/// Celda de teletransporte (token `T:<etiqueta>` o `T:<etiqueta>:<N|E|S|W>`). Cada etiqueta
+/// aparece exactamente dos veces y cada celda lleva a la otra; la orientación opcional es
+/// hacia dónde queda mirando el jugador al llegar a esa celda.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Teleporter {
+    pub label: String,
+    pub facing: Option<Facing>,
+    pub discovered: bool, // ya se usó; el minimapa dibuja el enlace
+}
+
+impl Teleporter {
+    pub fn new(label: &str, facing: Option<Facing>) -> Self {
+        Self { label: label.to_string(), facing, discovered: false }
+    }
+}
+
+// Etiqueta que se puede escribir en el formato de texto: letras, números y `_`
+pub(super) fn is_valid_label(label: &str) -> bool {
+    !label.is_empty() && label.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+// Primera etiqueta que no forma un par, con una de sus celdas
+pub(super) fn unpaired(teleporters: &BTreeMap<(i32, i32), Teleporter>) -> Option<((i32, i32), &str)> {
+    let mut count: BTreeMap<&str, ((i32, i32), usize)> = BTreeMap::new();
+    for (&cell, t) in teleporters {
+        count.entry(t.label.as_str()).or_insert((cell, 0)).1 += 1;
+    }
+    count.into_iter().find(|(_, (_, n))| *n != 2).map(|(label, (cell, _))| (cell, label))
+}
+
+impl Map {
+    pub fn teleporter_at(&self, x: i32, y: i32) -> Option<&Teleporter> {
+        self.teleporters.get(&(x, y))
+    }
+
+    /// Celda de llegada del teletransporte en `(x, y)`, con su orientación de llegada.
+    pub fn teleport_target(&self, x: i32, y: i32) -> Option<((i32, i32), Option<Facing>)> {
+        let from = self.teleporters.get(&(x, y))?;
+        self.teleporters.iter()
+            .find(|&(&cell, t)| cell != (x, y) && t.label == from.label)
+            .map(|(&cell, t)| (cell, t.facing))
+    }
+
+    /// Marca el par como descubierto (para el minimapa).
+    pub fn discover_teleporter(&mut self, x: i32, y: i32) {
+        let Some(label) = self.teleporters.get(&(x, y)).map(|t| t.label.clone()) else { return };
+        for t in self.teleporters.values_mut().filter(|t| t.label == label) {
+            t.discovered = true;
+        }
+    }
+}
@@ -0,0 +1,342 @@
+// Importador de mapas del editor Tiled (.tmx en XML, .tmj/.json en JSON).
+//
+// - La capa de tiles llamada "walls" (o la primera) da las paredes: gid 0 es piso y el
+//   tile n del primer tileset es la pared n. Las capas "floor" y "ceiling", si existen, dan
+//   los materiales de piso y techo con la misma numeración.
+// - Los objetos con tipo "spawn" (propiedad opcional `facing` = N/E/S/W), "goal", "door"
+//   (propiedad opcional `lock` = color) y "key" (propiedad `color`) marcan la aparición, las
+//   metas, las puertas y las llaves. Los "teleporter" se enlazan por la propiedad `label` (o su
+//   nombre) y aceptan `facing`. Cualquier otro objeto queda en `Map::entities`.
+// - Las propiedades del mapa (name, author, sky, floor, music, "wall 1"...) llenan `LevelMeta`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+use serde_json::Value;
+
+use super::{teleport, Door, Entity, Facing, KeyColor, LevelMeta, Location, Map, MapError, Spawn, Teleporter};
+
+// Bits de volteo que Tiled guarda en la parte alta del gid
+const GID_MASK: u32 = 0x1FFF_FFFF;
+
+pub fn is_tiled_path(path: &str) -> bool {
+    path.ends_with(".tmx") || path.ends_with(".tmj") || path.ends_with(".json")
+}
+
+impl Map {
+    /// Carga un mapa de Tiled; el formato se elige por extensión (.tmx = XML, si no JSON).
+    pub fn load_tiled(path: &str) -> Result<Map, MapError> {
+        let src = fs::read_to_string(path)
+            .map_err(|source| MapError::Io { at: Location::default(), source }.with_file(path))?;
+        let level = if path.ends_with(".tmx") { parse_tmx(&src) } else { parse_tiled_json(&src) };
+        level.map_err(|e| e.with_file(path))
+    }
+}
+
+// Datos comunes a ambos formatos antes de construir el `Map`
+struct RawMap {
+    w: i32,
+    h: i32,
+    tile_w: f32,
+    tile_h: f32,
+    first_gid: u32,
+    tiles: Vec<u32>,
+    floor: Option<Vec<u32>>,
+    ceiling: Option<Vec<u32>>,
+    properties: Vec<(String, String)>,
+    objects: Vec<RawObject>,
+}
+
+struct RawObject {
+    kind: String,
+    name: String,
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+    properties: BTreeMap<String, String>,
+}
+
+fn import_error(message: impl Into<String>) -> MapError {
+    MapError::Import { at: Location::default(), message: message.into() }
+}
+
+pub fn parse_tmx(src: &str) -> Result<Map, MapError> {
+    let doc = roxmltree::Document::parse(src).map_err(|e| {
+        let pos = e.pos();
+        MapError::Import { at: Location::new(pos.row as usize, pos.col as usize), message: e.to_string() }
+    })?;
+    let root = doc.root_element();
+    let attr = |node: roxmltree::Node, name: &str| -> Result<f32, MapError> {
+        node.attribute(name)
+            .and_then(|v| v.parse::<f32>().ok())
+            .ok_or_else(|| import_error(format!("falta el atributo numérico '{name}' en <{}>", node.tag_name().name())))
+    };
+    let properties = |node: roxmltree::Node| -> Vec<(String, String)> {
+        node.children()
+            .filter(|n| n.has_tag_name("properties"))
+            .flat_map(|p| p.children().filter(|n| n.has_tag_name("property")))
+            .filter_map(|p| {
+                let value = p.attribute("value").map(str::to_string).or_else(|| p.text().map(str::to_string))?;
+                Some((p.attribute("name")?.to_string(), value))
+            })
+            .collect()
+    };
+
+    let first_gid = root.children()
+        .find(|n| n.has_tag_name("tileset"))
+        .map(|t| attr(t, "firstgid"))
+        .transpose()?
+        .unwrap_or(1.0) as u32;
+
+    let layers: Vec<_> = root.children().filter(|n| n.has_tag_name("layer")).collect();
+    let named = |name: &str| layers.iter().find(|l| l.attribute("name") == Some(name));
+    let tiles_of = |layer: &roxmltree::Node| -> Result<Vec<u32>, MapError> {
+        let data = layer.children()
+            .find(|n| n.has_tag_name("data"))
+            .ok_or_else(|| import_error("la capa no tiene <data>"))?;
+        match data.attribute("encoding") {
+            Some("csv") => data.text().unwrap_or("")
+                .split(',')
+                .map(|t| t.trim())
+                .filter(|t| !t.is_empty())
+                .map(|t| t.parse::<u32>().map_err(|_| import_error(format!("gid inválido: {t}"))))
+                .collect(),
+            None => Ok(data.children()
+                .filter(|n| n.has_tag_name("tile"))
+                .map(|t| t.attribute("gid").and_then(|g| g.parse().ok()).unwrap_or(0))
+                .collect()),
+            Some(other) => Err(import_error(format!("codificación no soportada: {other} (usa CSV)"))),
+        }
+    };
+    let layer = named("walls")
+        .or(layers.first())
+        .ok_or_else(|| import_error("el mapa no tiene capa de tiles"))?;
+    let tiles = tiles_of(layer)?;
+    let floor = named("floor").map(tiles_of).transpose()?;
+    let ceiling = named("ceiling").map(tiles_of).transpose()?;
+
+    let mut objects = Vec::new();
+    for group in root.children().filter(|n| n.has_tag_name("objectgroup")) {
+        for o in group.children().filter(|n| n.has_tag_name("object")) {
+            objects.push(RawObject {
+                kind: o.attribute("type").or(o.attribute("class")).unwrap_or("").to_string(),
+                name: o.attribute("name").unwrap_or("").to_string(),
+                x: attr(o, "x")?,
+                y: attr(o, "y")?,
+                w: o.attribute("width").and_then(|v| v.parse().ok()).unwrap_or(0.0),
+                h: o.attribute("height").and_then(|v| v.parse().ok()).unwrap_or(0.0),
+                properties: properties(o).into_iter().collect(),
+            });
+        }
+    }
+
+    build(RawMap {
+        w: attr(root, "width")? as i32,
+        h: attr(root, "height")? as i32,
+        tile_w: attr(root, "tilewidth")?,
+        tile_h: attr(root, "tileheight")?,
+        first_gid,
+        tiles,
+        floor,
+        ceiling,
+        properties: properties(root),
+        objects,
+    })
+}
+
+pub fn parse_tiled_json(src: &str) -> Result<Map, MapError> {
+    let root: Value = serde_json::from_str(src).map_err(|e| MapError::Import {
+        at: Location::new(e.line(), e.column()),
+        message: e.to_string(),
+    })?;
+    let num = |v: &Value, key: &str| -> Result<f32, MapError> {
+        v.get(key)
+            .and_then(Value::as_f64)
+            .map(|n| n as f32)
+            .ok_or_else(|| import_error(format!("falta el campo numérico '{key}'")))
+    };
+    let text = |v: &Value, key: &str| v.get(key).and_then(Value::as_str).unwrap_or("").to_string();
+    let properties = |v: &Value| -> Vec<(String, String)> {
+        v.get("properties")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|p| {
+                let value = match p.get("value")? {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                Some((p.get("name")?.as_str()?.to_string(), value))
+            })
+            .collect()
+    };
+
+    let first_gid = root.get("tilesets")
+        .and_then(Value::as_array)
+        .and_then(|t| t.first())
+        .map(|t| num(t, "firstgid"))
+        .transpose()?
+        .unwrap_or(1.0) as u32;
+
+    let layers = root.get("layers").and_then(Value::as_array).map(Vec::as_slice).unwrap_or(&[]);
+    let tile_layers: Vec<&Value> = layers.iter().filter(|l| text(l, "type") == "tilelayer").collect();
+    let named = |name: &str| tile_layers.iter().find(|l| text(l, "name") == name);
+    let tiles_of = |layer: &&Value| -> Result<Vec<u32>, MapError> {
+        layer.get("data")
+            .and_then(Value::as_array)
+            .ok_or_else(|| import_error("la capa no tiene 'data' (usa codificación CSV)"))?
+            .iter()
+            .map(|g| g.as_u64().map(|g| g as u32).ok_or_else(|| import_error(format!("gid inválido: {g}"))))
+            .collect()
+    };
+    let layer = named("walls")
+        .or(tile_layers.first())
+        .ok_or_else(|| import_error("el mapa no tiene capa de tiles"))?;
+    let tiles = tiles_of(layer)?;
+    let floor = named("floor").map(tiles_of).transpose()?;
+    let ceiling = named("ceiling").map(tiles_of).transpose()?;
+
+    let mut objects = Vec::new();
+    for group in layers.iter().filter(|l| text(l, "type") == "objectgroup") {
+        for o in group.get("objects").and_then(Value::as_array).into_iter().flatten() {
+            let kind = match text(o, "type") {
+                k if k.is_empty() => text(o, "class"),
+                k => k,
+            };
+            objects.push(RawObject {
+                kind,
+                name: text(o, "name"),
+                x: num(o, "x")?,
+                y: num(o, "y")?,
+                w: num(o, "width").unwrap_or(0.0),
+                h: num(o, "height").unwrap_or(0.0),
+                properties: properties(o).into_iter().collect(),
+            });
+        }
+    }
+
+    build(RawMap {
+        w: num(&root, "width")? as i32,
+        h: num(&root, "height")? as i32,
+        tile_w: num(&root, "tilewidth")?,
+        tile_h: num(&root, "tileheight")?,
+        first_gid,
+        tiles,
+        floor,
+        ceiling,
+        properties: properties(&root),
+        objects,
+    })
+}
+
+// Propiedad opcional `facing` = N/E/S/W de un objeto
+fn facing(o: &RawObject) -> Result<Option<Facing>, MapError> {
+    o.properties.get("facing")
+        .map(|f| Facing::from_letter(f).ok_or_else(|| import_error(format!("orientación inválida: {f}"))))
+        .transpose()
+}
+
+fn build(raw: RawMap) -> Result<Map, MapError> {
+    if raw.w <= 0 || raw.h <= 0 || raw.tiles.is_empty() {
+        return Err(MapError::Empty { at: Location::default() });
+    }
+    if raw.tiles.len() != (raw.w * raw.h) as usize {
+        return Err(import_error(format!(
+            "la capa tiene {} tiles, se esperaban {}x{}", raw.tiles.len(), raw.w, raw.h
+        )));
+    }
+
+    let ids = |tiles: &[u32]| -> Result<Vec<u8>, MapError> {
+        if tiles.len() != raw.tiles.len() {
+            return Err(import_error(format!("una capa tiene {} tiles, se esperaban {}", tiles.len(), raw.tiles.len())));
+        }
+        tiles.iter()
+            .map(|&gid| match gid & GID_MASK {
+                0 => Ok(0),
+                g => u8::try_from(g.saturating_sub(raw.first_gid) + 1)
+                    .map_err(|_| import_error(format!("gid {g} no cabe en un id de pared"))),
+            })
+            .collect()
+    };
+    let cells = ids(&raw.tiles)?;
+    let floor = raw.floor.as_deref().map(ids).transpose()?;
+    let ceiling = raw.ceiling.as_deref().map(ids).transpose()?;
+
+    let mut meta = LevelMeta::default();
+    for (key, value) in &raw.properties {
+        meta.set(key, value).map_err(import_error)?;
+    }
+
+    let cell_of = |x: f32, y: f32| ((x / raw.tile_w).floor() as i32, (y / raw.tile_h).floor() as i32);
+    let mut spawn = None;
+    let mut goals = BTreeSet::new();
+    let mut doors = BTreeMap::new();
+    let mut keys = BTreeMap::new();
+    let mut teleporters = BTreeMap::new();
+    let mut entities = Vec::new();
+    let color = |name: &str| KeyColor::from_name(name).ok_or_else(|| import_error(format!("color de llave inválido: {name}")));
+    for o in raw.objects {
+        match o.kind.as_str() {
+            "spawn" => {
+                if spawn.is_some() { return Err(MapError::DuplicateSpawn { at: Location::default() }); }
+                let facing = facing(&o)?.unwrap_or_default();
+                let (x, y) = cell_of(o.x, o.y);
+                spawn = Some(Spawn { x, y, facing });
+            }
+            "goal" => {
+                // Un rectángulo marca todas las celdas que toca; un punto, solo la suya
+                let (x0, y0) = cell_of(o.x, o.y);
+                let (x1, y1) = cell_of(o.x + (o.w - 0.01).max(0.0), o.y + (o.h - 0.01).max(0.0));
+                goals.extend((y0..=y1).flat_map(|y| (x0..=x1).map(move |x| (x, y))));
+            }
+            "door" => {
+                let door = match o.properties.get("lock") {
+                    Some(c) => Door::locked(color(c)?),
+                    None => Door::default(),
+                };
+                doors.insert(cell_of(o.x, o.y), door);
+            }
+            "key" => {
+                let c = o.properties.get("color").ok_or_else(|| import_error("llave sin propiedad 'color'"))?;
+                keys.insert(cell_of(o.x, o.y), color(c)?);
+            }
+            "teleporter" => {
+                let label = o.properties.get("label").unwrap_or(&o.name);
+                if label.is_empty() { return Err(import_error("teletransporte sin etiqueta")); }
+                // La misma regla que `T:<etiqueta>`, para que el nivel se pueda guardar
+                if !teleport::is_valid_label(label) {
+                    return Err(import_error(format!("etiqueta de teletransporte inválida (solo letras, números y _): {label}")));
+                }
+                teleporters.insert(cell_of(o.x, o.y), Teleporter::new(label, facing(&o)?));
+            }
+            _ => entities.push(Entity {
+                kind: o.kind,
+                name: o.name,
+                x: o.x / raw.tile_w,
+                y: o.y / raw.tile_h,
+                properties: o.properties,
+            }),
+        }
+    }
+    let spawn = spawn.ok_or(MapError::MissingSpawn { at: Location::default() })?;
+    if let Some((_, label)) = teleport::unpaired(&teleporters) {
+        return Err(MapError::UnpairedTeleporter { at: Location::default(), label: label.to_string() });
+    }
+
+    let mut map = Map::new(raw.w, raw.h, cells, spawn);
+    map.meta = meta;
+    if let Some(floor) = floor { map.floor = floor; }
+    if let Some(ceiling) = ceiling { map.ceiling = ceiling; }
+    // Metas, puertas, llaves y teletransportes ocupan celdas vacías
+    for &(x, y) in goals.iter().chain(doors.keys()).chain(keys.keys()).chain(teleporters.keys()) {
+        if let Some(i) = map.index(x, y) { map.cells[i] = 0; }
+    }
+    let inside = |&(x, y): &(i32, i32)| x >= 0 && y >= 0 && x < raw.w && y < raw.h;
+    map.goals = goals.into_iter().filter(inside).collect();
+    map.doors = doors.into_iter().filter(|(c, _)| inside(c)).collect();
+    map.keys = keys.into_iter().filter(|(c, _)| inside(c)).collect();
+    map.teleporters = teleporters.into_iter().filter(|(c, _)| inside(c)).collect();
+    map.entities = entities;
+    Ok(map)
+}
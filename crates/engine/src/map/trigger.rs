@@ -0,0 +1,172 @@
+// Disparadores de la sección `[triggers]`, una regla por línea:
+//
+//   enter 3 4 -> open 5 5
+//   enter 2 2 4 3 -> wall 7 2 3; message ¡Algo se movió!
+//   pickup red -> sound assets/sfx/selectlevel.wav
+//   after 60 -> message Se acabó el tiempo; end
+//
+// Condiciones: `enter x y` (o un rectángulo `x0 y0 x1 y1`), `pickup <color>` y `after <segundos>`.
+// Acciones (separadas por `;`): `open x y`, `wall x y id`, `sound <ruta>`, `message <texto>` y
+// `end`. Cada regla se dispara una sola vez.
+
+use std::fmt;
+
+use super::{KeyColor, Map};
+use crate::Player;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    /// El jugador entra al rectángulo (inclusivo).
+    Enter { x0: i32, y0: i32, x1: i32, y1: i32 },
+    Pickup(KeyColor),
+    /// Segundos desde el inicio del nivel.
+    After(f32),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    /// Cambia el id de la celda (`open` = 0).
+    SetCell { x: i32, y: i32, id: u8 },
+    Sound(String),
+    Message(String),
+    EndLevel,
+}
+
+/// Efecto que el motor no puede aplicar solo y devuelve al juego.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TriggerEvent {
+    Sound(String),
+    Message(String),
+    EndLevel,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trigger {
+    pub when: Condition,
+    pub actions: Vec<Action>,
+    pub fired: bool,
+}
+
+impl Trigger {
+    pub(super) fn parse(line: &str) -> Result<Self, String> {
+        let (when, actions) = line.split_once("->")
+            .ok_or_else(|| format!("se esperaba 'condición -> acciones': {line}"))?;
+        let actions = actions.split(';')
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .map(parse_action)
+            .collect::<Result<Vec<_>, _>>()?;
+        if actions.is_empty() {
+            return Err(format!("disparador sin acciones: {line}"));
+        }
+        Ok(Self { when: parse_condition(when.trim())?, actions, fired: false })
+    }
+
+    fn is_met(&self, time: f32, cell: (i32, i32), picked: Option<KeyColor>) -> bool {
+        match self.when {
+            Condition::Enter { x0, y0, x1, y1 } => {
+                (x0..=x1).contains(&cell.0) && (y0..=y1).contains(&cell.1)
+            }
+            Condition::Pickup(color) => picked == Some(color),
+            Condition::After(secs) => time >= secs,
+        }
+    }
+}
+
+fn numbers<T: std::str::FromStr>(args: &str, what: &str) -> Result<Vec<T>, String> {
+    args.split_whitespace()
+        .map(|n| n.parse::<T>().map_err(|_| format!("número inválido en '{what}': {n}")))
+        .collect()
+}
+
+fn parse_condition(s: &str) -> Result<Condition, String> {
+    let (word, args) = s.split_once(' ').unwrap_or((s, ""));
+    match word {
+        "enter" => match numbers::<i32>(args, s)?[..] {
+            [x, y] => Ok(Condition::Enter { x0: x, y0: y, x1: x, y1: y }),
+            [x0, y0, x1, y1] => Ok(Condition::Enter { x0: x0.min(x1), y0: y0.min(y1), x1: x0.max(x1), y1: y0.max(y1) }),
+            _ => Err(format!("'enter' espera 'x y' o 'x0 y0 x1 y1': {s}")),
+        },
+        "pickup" => KeyColor::from_name(args.trim())
+            .map(Condition::Pickup)
+            .ok_or_else(|| format!("color de llave inválido: {}", args.trim())),
+        "after" => args.trim().parse::<f32>()
+            .ok()
+            .filter(|t| *t >= 0.0)
+            .map(Condition::After)
+            .ok_or_else(|| format!("tiempo inválido: {}", args.trim())),
+        _ => Err(format!("condición desconocida: {word}")),
+    }
+}
+
+fn parse_action(s: &str) -> Result<Action, String> {
+    let (word, args) = s.split_once(' ').unwrap_or((s, ""));
+    let args = args.trim();
+    match word {
+        "open" => match numbers::<i32>(args, s)?[..] {
+            [x, y] => Ok(Action::SetCell { x, y, id: 0 }),
+            _ => Err(format!("'open' espera 'x y': {s}")),
+        },
+        "wall" => match (numbers::<i32>(args, s), args.split_whitespace().nth(2).map(str::parse::<u8>)) {
+            (Ok(n), Some(Ok(id))) if n.len() == 3 && id != 0 => Ok(Action::SetCell { x: n[0], y: n[1], id }),
+            _ => Err(format!("'wall' espera 'x y id' (id 1-255): {s}")),
+        },
+        "sound" if !args.is_empty() => Ok(Action::Sound(args.to_string())),
+        "message" if !args.is_empty() => Ok(Action::Message(args.to_string())),
+        "end" if args.is_empty() => Ok(Action::EndLevel),
+        _ => Err(format!("acción inválida: {s}")),
+    }
+}
+
+// Misma sintaxis que lee `Trigger::parse`
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.when {
+            Condition::Enter { x0, y0, x1, y1 } if (x0, y0) == (x1, y1) => write!(f, "enter {x0} {y0}")?,
+            Condition::Enter { x0, y0, x1, y1 } => write!(f, "enter {x0} {y0} {x1} {y1}")?,
+            Condition::Pickup(color) => write!(f, "pickup {}", color.name())?,
+            Condition::After(secs) => write!(f, "after {secs}")?,
+        }
+        write!(f, " ->")?;
+        for (i, action) in self.actions.iter().enumerate() {
+            if i > 0 { write!(f, ";")?; }
+            match action {
+                Action::SetCell { x, y, id: 0 } => write!(f, " open {x} {y}")?,
+                Action::SetCell { x, y, id } => write!(f, " wall {x} {y} {id}")?,
+                Action::Sound(path) => write!(f, " sound {path}")?,
+                Action::Message(text) => write!(f, " message {text}")?,
+                Action::EndLevel => write!(f, " end")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Map {
+    /// Revisa los disparadores pendientes y aplica los que se cumplen. `time` son los segundos
+    /// desde el inicio del nivel y `picked` la llave recogida en este cuadro. Los cambios de
+    /// celda se hacen aquí; sonidos, mensajes y fin de nivel se devuelven al juego.
+    pub fn update_triggers(&mut self, time: f32, player: &Player, picked: Option<KeyColor>) -> Vec<TriggerEvent> {
+        let cell = (player.pos.x.floor() as i32, player.pos.y.floor() as i32);
+        let mut events = Vec::new();
+        let mut changes = Vec::new();
+        for trigger in self.triggers.iter_mut().filter(|t| !t.fired) {
+            if !trigger.is_met(time, cell, picked) { continue; }
+            trigger.fired = true;
+            for action in &trigger.actions {
+                match action {
+                    Action::SetCell { x, y, id } => changes.push((*x, *y, *id)),
+                    Action::Sound(path) => events.push(TriggerEvent::Sound(path.clone())),
+                    Action::Message(text) => events.push(TriggerEvent::Message(text.clone())),
+                    Action::EndLevel => events.push(TriggerEvent::EndLevel),
+                }
+            }
+        }
+        for (x, y, id) in changes {
+            if let Some(i) = self.index(x, y) {
+                self.cells[i] = id;
+            }
+        }
+        events
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
+
+use super::trigger::{Action, Condition};
+use super::{KeyColor, Map};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Problema encontrado por `Map::validate`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// Celda del borde que no es pared.
+    OpenBorder { x: i32, y: i32 },
+    /// El mapa no tiene ninguna celda de meta.
+    NoGoal,
+    /// No existe camino desde la aparición hasta la meta.
+    GoalUnreachable { x: i32, y: i32 },
+    /// Zona abierta sin conexión con la aparición; `(x, y)` es una de sus celdas.
+    IsolatedRegion { x: i32, y: i32, cells: usize },
+    /// Id de pared sin color en `[meta]`; `(x, y)` es su primera aparición.
+    UnknownWallId { id: u8, x: i32, y: i32 },
+    /// Material de piso o techo sin color en `[meta]`; `(x, y)` es su primera aparición.
+    UnknownMaterial { layer: &'static str, id: u8, x: i32, y: i32 },
+    /// La aparición está dentro de una pared o fuera del mapa.
+    SpawnInWall { x: i32, y: i32 },
+    /// Puerta con cerrojo cuya llave no existe o no se puede alcanzar.
+    MissingKey { color: KeyColor, x: i32, y: i32 },
+    /// Disparador (por su posición en `[triggers]`, desde 1) que usa una celda fuera del mapa.
+    TriggerOutside { trigger: usize, x: i32, y: i32 },
+}
+
+impl Diagnostic {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Diagnostic::IsolatedRegion { .. }
+            | Diagnostic::UnknownWallId { .. }
+            | Diagnostic::UnknownMaterial { .. }
+            | Diagnostic::MissingKey { .. }
+            | Diagnostic::TriggerOutside { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity() == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Diagnostic::OpenBorder { x, y } => write!(f, "borde abierto en ({x}, {y})"),
+            Diagnostic::NoGoal => write!(f, "el mapa no tiene meta"),
+            Diagnostic::GoalUnreachable { x, y } => write!(f, "la meta en ({x}, {y}) no es alcanzable"),
+            Diagnostic::IsolatedRegion { x, y, cells } => {
+                write!(f, "zona aislada de {cells} celdas en ({x}, {y})")
+            }
+            Diagnostic::UnknownWallId { id, x, y } => {
+                write!(f, "pared {id} sin color en ({x}, {y})")
+            }
+            Diagnostic::UnknownMaterial { layer, id, x, y } => {
+                write!(f, "material {id} de [{layer}] sin color en ({x}, {y})")
+            }
+            Diagnostic::SpawnInWall { x, y } => write!(f, "la aparición ({x}, {y}) está en una pared"),
+            Diagnostic::MissingKey { color, x, y } => {
+                write!(f, "la puerta ({x}, {y}) necesita la llave {} y no se puede conseguir", color.name())
+            }
+            Diagnostic::TriggerOutside { trigger, x, y } => {
+                write!(f, "el disparador {trigger} usa la celda ({x}, {y}), fuera del mapa")
+            }
+        }
+    }
+}
+
+impl Map {
+    /// Revisa el mapa y devuelve todos los problemas encontrados (vacío = mapa correcto).
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut out = Vec::new();
+
+        // Borde sólido
+        for y in 0..self.h {
+            for x in 0..self.w {
+                let border = x == 0 || y == 0 || x == self.w - 1 || y == self.h - 1;
+                if border && !self.is_wall(x, y) {
+                    out.push(Diagnostic::OpenBorder { x, y });
+                }
+            }
+        }
+
+        // Celdas de los disparadores
+        for (n, trigger) in self.triggers.iter().enumerate() {
+            let mut cells = Vec::new();
+            if let Condition::Enter { x0, y0, x1, y1 } = trigger.when {
+                cells.extend([(x0, y0), (x1, y1)]);
+            }
+            cells.extend(trigger.actions.iter().filter_map(|a| match *a {
+                Action::SetCell { x, y, .. } => Some((x, y)),
+                _ => None,
+            }));
+            if let Some(&(x, y)) = cells.iter().find(|&&(x, y)| self.index(x, y).is_none()) {
+                out.push(Diagnostic::TriggerOutside { trigger: n + 1, x, y });
+            }
+        }
+
+        // Ids de pared sin color
+        let mut unknown: BTreeMap<u8, (i32, i32)> = BTreeMap::new();
+        for y in 0..self.h {
+            for x in 0..self.w {
+                let id = self.get(x, y);
+                if id > 0 && !self.meta.wall_colors.contains_key(&id) {
+                    unknown.entry(id).or_insert((x, y));
+                }
+            }
+        }
+        out.extend(unknown.into_iter().map(|(id, (x, y))| Diagnostic::UnknownWallId { id, x, y }));
+
+        // Materiales sin color
+        for (layer, cells, colors) in [
+            ("floor", &self.floor, &self.meta.floor_colors),
+            ("ceiling", &self.ceiling, &self.meta.ceiling_colors),
+        ] {
+            let mut unknown: BTreeMap<u8, (i32, i32)> = BTreeMap::new();
+            for (i, &id) in cells.iter().enumerate() {
+                if id > 0 && !colors.contains_key(&id) {
+                    unknown.entry(id).or_insert((i as i32 % self.w, i as i32 / self.w));
+                }
+            }
+            out.extend(unknown.into_iter().map(|(id, (x, y))| Diagnostic::UnknownMaterial { layer, id, x, y }));
+        }
+
+        let (sx, sy) = (self.spawn.x, self.spawn.y);
+        if self.is_wall(sx, sy) {
+            out.push(Diagnostic::SpawnInWall { x: sx, y: sy });
+            return out;
+        }
+
+        // Alcanzabilidad desde la aparición: cada llave alcanzada abre más puertas, así que se
+        // repite hasta que no aparezcan llaves nuevas
+        let mut keys = BTreeSet::new();
+        let reached = loop {
+            let reached = self.flood(sx, sy, &keys);
+            let found: BTreeSet<KeyColor> = self.keys.iter()
+                .filter(|&(&(x, y), _)| self.index(x, y).is_some_and(|i| reached[i]))
+                .map(|(_, &color)| color)
+                .collect();
+            if found.is_subset(&keys) { break reached; }
+            keys.extend(found);
+        };
+        if self.goals.is_empty() {
+            out.push(Diagnostic::NoGoal);
+        }
+        for &(gx, gy) in &self.goals {
+            if !self.index(gx, gy).is_some_and(|i| reached[i]) {
+                out.push(Diagnostic::GoalUnreachable { x: gx, y: gy });
+            }
+        }
+
+        for (&(x, y), door) in &self.doors {
+            if let Some(color) = door.lock.filter(|c| !keys.contains(c)) {
+                out.push(Diagnostic::MissingKey { color, x, y });
+            }
+        }
+
+        // Zonas abiertas no conectadas (contando todas las puertas como abiertas)
+        let all_keys = KeyColor::ALL.into_iter().collect();
+        let mut seen = self.flood(sx, sy, &all_keys);
+        for y in 0..self.h {
+            for x in 0..self.w {
+                let i = (y * self.w + x) as usize;
+                if seen[i] || self.is_wall(x, y) { continue; }
+                let region = self.flood(x, y, &all_keys);
+                let cells = region.iter().filter(|&&r| r).count();
+                for (s, r) in seen.iter_mut().zip(region) { *s |= r; }
+                out.push(Diagnostic::IsolatedRegion { x, y, cells });
+            }
+        }
+
+        out
+    }
+
+    // BFS 4-conexo sobre celdas sin pared. Las puertas se pueden abrir, salvo las que tienen
+    // cerrojo de un color que no está en `keys`, y las celdas que abre algún disparador cuentan
+    // como abiertas.
+    fn flood(&self, x: i32, y: i32, keys: &BTreeSet<KeyColor>) -> Vec<bool> {
+        let opened: BTreeSet<(i32, i32)> = self.triggers.iter()
+            .flat_map(|t| &t.actions)
+            .filter_map(|a| match *a {
+                Action::SetCell { x, y, id: 0 } => Some((x, y)),
+                _ => None,
+            })
+            .collect();
+        let mut seen = vec![false; self.cells.len()];
+        let mut queue = VecDeque::new();
+        if let Some(i) = self.index(x, y) {
+            seen[i] = true;
+            queue.push_back((x, y));
+        }
+        while let Some((cx, cy)) = queue.pop_front() {
+            // Un teletransporte también conecta con su pareja
+            let jump = self.teleport_target(cx, cy).map(|(cell, _)| cell);
+            for (nx, ny) in [(cx + 1, cy), (cx - 1, cy), (cx, cy + 1), (cx, cy - 1)].into_iter().chain(jump) {
+                if self.is_wall(nx, ny) && !opened.contains(&(nx, ny)) { continue; }
+                let lock = self.door_at(nx, ny).and_then(|d| d.lock);
+                if lock.is_some_and(|c| !keys.contains(&c)) { continue; }
+                if let Some(i) = self.index(nx, ny) {
+                    if !seen[i] {
+                        seen[i] = true;
+                        queue.push_back((nx, ny));
+                    }
+                }
+            }
+        }
+        seen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(src: &str) -> Map {
+        Map::parse_str(src).unwrap()
+    }
+
+    #[test]
+    fn valid_map_has_no_diagnostics() {
+        assert_eq!(map("1 1 1 1\n1 S 9 1\n1 1 1 1\n").validate(), vec![]);
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        let d = map("1 1 1 1 1\n1 S 1 9 1\n1 1 1 1 1\n").validate();
+        assert_eq!(d, vec![
+            Diagnostic::GoalUnreachable { x: 3, y: 1 },
+            Diagnostic::IsolatedRegion { x: 3, y: 1, cells: 1 },
+        ]);
+        assert!(d[0].is_error());
+    }
+
+    #[test]
+    fn open_border() {
+        let d = map("1 1 1\n1 S 0\n1 9 1\n1 1 1\n").validate();
+        assert_eq!(d, vec![Diagnostic::OpenBorder { x: 2, y: 1 }]);
+        assert!(d[0].is_error());
+    }
+
+    #[test]
+    fn spawn_in_wall() {
+        let mut m = map("1 1 1 1\n1 S 9 1\n1 1 1 1\n");
+        let i = m.index(1, 1).unwrap();
+        m.cells[i] = 1;
+        assert_eq!(m.validate(), vec![Diagnostic::SpawnInWall { x: 1, y: 1 }]);
+    }
+
+    #[test]
+    fn unknown_wall_id_is_a_warning() {
+        let d = map("1 1 1 1\n1 S 9 7\n1 1 1 1\n").validate();
+        assert_eq!(d, vec![Diagnostic::UnknownWallId { id: 7, x: 3, y: 1 }]);
+        assert_eq!(d[0].severity(), Severity::Warning);
+    }
+}
@@ -0,0 +1,373 @@
+// Generador procedural de laberintos. Cada celda del laberinto ocupa una celda impar del
+// `Map` y los pasillos entre celdas se abren en las posiciones pares intermedias, así que
+// un laberinto de `width` x `height` produce un mapa de (2*width+1) x (2*height+1).
+
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::map::{Facing, Spawn};
+use crate::Map;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    Backtracker,
+    Prim,
+    Kruskal,
+    Eller,
+    /// Backtracker sin la mayoría de callejones sin salida (tiene ciclos).
+    Braided,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 5] = [
+        Algorithm::Backtracker,
+        Algorithm::Prim,
+        Algorithm::Kruskal,
+        Algorithm::Eller,
+        Algorithm::Braided,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Backtracker => "Backtracker",
+            Algorithm::Prim => "Prim",
+            Algorithm::Kruskal => "Kruskal",
+            Algorithm::Eller => "Eller",
+            Algorithm::Braided => "Trenzado",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MazeConfig {
+    pub seed: u64,
+    pub width: i32,  // en celdas de laberinto
+    pub height: i32,
+    pub algorithm: Algorithm,
+}
+
+// Probabilidad de abrir cada callejón sin salida en `Algorithm::Braided`
+const BRAID_CHANCE: f32 = 0.75;
+
+/// Genera un laberinto perfecto (o trenzado) con aparición y meta en los extremos de su
+/// camino más largo, así la meta siempre queda a la máxima distancia posible de la aparición.
+pub fn generate(cfg: &MazeConfig) -> Map {
+    let cw = cfg.width.max(2);
+    let ch = cfg.height.max(2);
+    let mut rng = Rng::new(cfg.seed);
+    let mut grid = Grid::new(cw, ch);
+
+    match cfg.algorithm {
+        Algorithm::Backtracker => backtracker(&mut grid, &mut rng),
+        Algorithm::Prim => prim(&mut grid, &mut rng),
+        Algorithm::Kruskal => kruskal(&mut grid, &mut rng),
+        Algorithm::Eller => eller(&mut grid, &mut rng),
+        Algorithm::Braided => {
+            backtracker(&mut grid, &mut rng);
+            braid(&mut grid, &mut rng, BRAID_CHANCE);
+        }
+    }
+
+    // Diámetro del laberinto: lo más lejano desde una celda cualquiera, y luego lo más
+    // lejano desde ahí
+    let start = rng.below(grid.len());
+    let a = grid.farthest(start);
+    let b = grid.farthest(a);
+
+    let w = 2 * cw + 1;
+    let h = 2 * ch + 1;
+    let mut cells = vec![0u8; (w * h) as usize];
+    for y in 0..h {
+        for x in 0..w {
+            let open = match (x % 2, y % 2) {
+                (1, 1) => true,
+                (0, 1) => x > 0 && x < w - 1 && grid.has_east((x - 2) / 2, (y - 1) / 2),
+                (1, 0) => y > 0 && y < h - 1 && grid.has_south((x - 1) / 2, (y - 2) / 2),
+                _ => false,
+            };
+            if !open {
+                cells[(y * w + x) as usize] = 1 + rng.below(3) as u8;
+            }
+        }
+    }
+
+    let (sx, sy) = grid.coords(a);
+    let (gx, gy) = grid.coords(b);
+    // Mirar hacia el primer pasillo abierto
+    let facing = [Facing::East, Facing::South, Facing::West, Facing::North]
+        .into_iter()
+        .find(|&f| grid.neighbors(a).contains(&grid.step(a, f)))
+        .unwrap_or_default();
+
+    let mut map = Map::new(w, h, cells, Spawn { x: 2 * sx + 1, y: 2 * sy + 1, facing });
+    map.goals.insert((2 * gx + 1, 2 * gy + 1));
+    map.meta.name = Some(format!("{} #{}", cfg.algorithm.name(), cfg.seed));
+    map
+}
+
+// Pasillos abiertos entre celdas vecinas del laberinto
+struct Grid {
+    w: i32,
+    h: i32,
+    east: Vec<bool>,  // celda (x,y) conectada con (x+1,y)
+    south: Vec<bool>, // celda (x,y) conectada con (x,y+1)
+}
+
+impl Grid {
+    fn new(w: i32, h: i32) -> Self {
+        let n = (w * h) as usize;
+        Self { w, h, east: vec![false; n], south: vec![false; n] }
+    }
+
+    fn len(&self) -> usize { (self.w * self.h) as usize }
+
+    fn coords(&self, i: usize) -> (i32, i32) {
+        (i as i32 % self.w, i as i32 / self.w)
+    }
+
+    fn idx(&self, x: i32, y: i32) -> usize { (y * self.w + x) as usize }
+
+    fn has_east(&self, x: i32, y: i32) -> bool { self.east[self.idx(x, y)] }
+
+    fn has_south(&self, x: i32, y: i32) -> bool { self.south[self.idx(x, y)] }
+
+    // Celdas adyacentes dentro de la cuadrícula (con o sin pasillo)
+    fn adjacent(&self, i: usize) -> Vec<usize> {
+        let (x, y) = self.coords(i);
+        let mut out = Vec::with_capacity(4);
+        if x > 0 { out.push(i - 1); }
+        if x < self.w - 1 { out.push(i + 1); }
+        if y > 0 { out.push(i - self.w as usize); }
+        if y < self.h - 1 { out.push(i + self.w as usize); }
+        out
+    }
+
+    // Celdas conectadas por un pasillo
+    fn neighbors(&self, i: usize) -> Vec<usize> {
+        self.adjacent(i).into_iter().filter(|&j| self.connected(i, j)).collect()
+    }
+
+    fn step(&self, i: usize, f: Facing) -> usize {
+        let (x, y) = self.coords(i);
+        let d = f.dir();
+        let (nx, ny) = (x + d.x as i32, y + d.y as i32);
+        if nx < 0 || ny < 0 || nx >= self.w || ny >= self.h { i } else { self.idx(nx, ny) }
+    }
+
+    fn connected(&self, a: usize, b: usize) -> bool {
+        let (a, b) = (a.min(b), a.max(b));
+        if b == a + 1 && b as i32 % self.w != 0 { self.east[a] }
+        else if b == a + self.w as usize { self.south[a] }
+        else { false }
+    }
+
+    fn carve(&mut self, a: usize, b: usize) {
+        let (a, b) = (a.min(b), a.max(b));
+        if b == a + 1 { self.east[a] = true; } else { self.south[a] = true; }
+    }
+
+    // Celda más lejana (en pasos) desde `from`
+    fn farthest(&self, from: usize) -> usize {
+        let mut dist = vec![usize::MAX; self.len()];
+        let mut queue = VecDeque::from([from]);
+        dist[from] = 0;
+        let mut last = from;
+        while let Some(i) = queue.pop_front() {
+            last = i;
+            for j in self.neighbors(i) {
+                if dist[j] == usize::MAX {
+                    dist[j] = dist[i] + 1;
+                    queue.push_back(j);
+                }
+            }
+        }
+        last
+    }
+}
+
+fn backtracker(grid: &mut Grid, rng: &mut Rng) {
+    let mut visited = vec![false; grid.len()];
+    let start = rng.below(grid.len());
+    let mut stack = vec![start];
+    visited[start] = true;
+    while let Some(&cur) = stack.last() {
+        let options: Vec<usize> = grid.adjacent(cur).into_iter().filter(|&n| !visited[n]).collect();
+        if options.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let next = options[rng.below(options.len())];
+        grid.carve(cur, next);
+        visited[next] = true;
+        stack.push(next);
+    }
+}
+
+fn prim(grid: &mut Grid, rng: &mut Rng) {
+    let mut in_maze = vec![false; grid.len()];
+    let mut frontier: Vec<usize> = Vec::new();
+    let start = rng.below(grid.len());
+    in_maze[start] = true;
+    frontier.extend(grid.adjacent(start));
+    while !frontier.is_empty() {
+        let cell = frontier.swap_remove(rng.below(frontier.len()));
+        if in_maze[cell] { continue; }
+        let linked: Vec<usize> = grid.adjacent(cell).into_iter().filter(|&n| in_maze[n]).collect();
+        grid.carve(cell, linked[rng.below(linked.len())]);
+        in_maze[cell] = true;
+        frontier.extend(grid.adjacent(cell).into_iter().filter(|&n| !in_maze[n]));
+    }
+}
+
+fn kruskal(grid: &mut Grid, rng: &mut Rng) {
+    let mut edges: Vec<(usize, usize)> = (0..grid.len())
+        .flat_map(|i| grid.adjacent(i).into_iter().filter(move |&j| j > i).map(move |j| (i, j)))
+        .collect();
+    rng.shuffle(&mut edges);
+    let mut sets = DisjointSet::new(grid.len());
+    for (a, b) in edges {
+        if sets.union(a, b) {
+            grid.carve(a, b);
+        }
+    }
+}
+
+fn eller(grid: &mut Grid, rng: &mut Rng) {
+    let w = grid.w as usize;
+    let mut sets = DisjointSet::new(grid.len());
+    for y in 0..grid.h {
+        let last_row = y == grid.h - 1;
+        let row = grid.idx(0, y);
+
+        // Unir vecinos horizontales al azar (en la última fila, todos los conjuntos)
+        for x in 0..w - 1 {
+            let (a, b) = (row + x, row + x + 1);
+            if sets.find(a) != sets.find(b) && (last_row || rng.chance(0.5)) {
+                sets.union(a, b);
+                grid.carve(a, b);
+            }
+        }
+        if last_row { break; }
+
+        // Al menos una bajada por conjunto
+        let mut order: Vec<usize> = (0..w).collect();
+        rng.shuffle(&mut order);
+        let mut has_down = BTreeSet::new();
+        for x in order {
+            let a = row + x;
+            let root = sets.find(a);
+            if !has_down.contains(&root) || rng.chance(0.3) {
+                has_down.insert(root);
+                sets.union(a, a + w);
+                grid.carve(a, a + w);
+            }
+        }
+    }
+}
+
+// Abre callejones sin salida para crear ciclos
+fn braid(grid: &mut Grid, rng: &mut Rng, chance: f32) {
+    let mut cells: Vec<usize> = (0..grid.len()).collect();
+    rng.shuffle(&mut cells);
+    for i in cells {
+        if grid.neighbors(i).len() != 1 || !rng.chance(chance) { continue; }
+        let closed: Vec<usize> = grid.adjacent(i).into_iter().filter(|&j| !grid.connected(i, j)).collect();
+        if closed.is_empty() { continue; }
+        // Preferir otro callejón para eliminar dos de una vez
+        let dead: Vec<usize> = closed.iter().copied().filter(|&j| grid.neighbors(j).len() == 1).collect();
+        let pick = if dead.is_empty() { closed[rng.below(closed.len())] } else { dead[rng.below(dead.len())] };
+        grid.carve(i, pick);
+    }
+}
+
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(n: usize) -> Self { Self { parent: (0..n).collect() } }
+
+    fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.parent[root] != root { root = self.parent[root]; }
+        let mut cur = i;
+        while self.parent[cur] != root {
+            let next = self.parent[cur];
+            self.parent[cur] = root;
+            cur = next;
+        }
+        root
+    }
+
+    // true si estaban en conjuntos distintos
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra == rb { return false; }
+        self.parent[rb] = ra;
+        true
+    }
+}
+
+// SplitMix64: suficiente para laberintos y reproducible entre plataformas
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self { Self(seed) }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    fn chance(&mut self, p: f32) -> bool {
+        ((self.next_u64() >> 40) as f32 / (1u64 << 24) as f32) < p
+    }
+
+    fn shuffle<T>(&mut self, v: &mut [T]) {
+        for i in (1..v.len()).rev() {
+            v.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZES: [(i32, i32); 7] = [(1, 1), (2, 2), (3, 5), (8, 8), (15, 4), (12, 20), (20, 20)];
+
+    fn config(algorithm: Algorithm, seed: u64, (width, height): (i32, i32)) -> MazeConfig {
+        MazeConfig { seed, width, height, algorithm }
+    }
+
+    #[test]
+    fn same_seed_same_maze() {
+        for algorithm in Algorithm::ALL {
+            for size in SIZES {
+                let cfg = config(algorithm, 42, size);
+                assert_eq!(generate(&cfg), generate(&cfg), "{} {size:?}", algorithm.name());
+            }
+            let (a, b) = (config(algorithm, 1, (12, 12)), config(algorithm, 2, (12, 12)));
+            assert_ne!(generate(&a).cells, generate(&b).cells, "{}", algorithm.name());
+        }
+    }
+
+    #[test]
+    fn every_maze_validates() {
+        for algorithm in Algorithm::ALL {
+            for size in SIZES {
+                for seed in 0..200 {
+                    let map = generate(&config(algorithm, seed, size));
+                    let problems = map.validate();
+                    assert!(problems.is_empty(), "{} {size:?} semilla {seed}: {problems:?}", algorithm.name());
+                }
+            }
+        }
+    }
+}
@@ -1,11 +1,131 @@
+use std::collections::BTreeSet;
+
 use glam::Vec2;
 use crate::Map;
+use crate::map::{Facing, KeyColor, Spawn};
+use crate::raycast::Camera;
+
+/// Campo de visión vertical por defecto, en grados: con tan(fov/2) = 0.5 las paredes tienen
+/// el mismo alto que antes de poder configurarlo.
+pub const DEFAULT_FOV: f32 = 53.13;
+// Proporción de pantalla que se supone hasta que se llama a `set_fov`
+const DEFAULT_ASPECT: f32 = 16.0 / 9.0;
+
+// Largo del plano de cámara para un campo de visión vertical y una proporción ancho/alto
+fn plane_length(fov: f32, aspect: f32) -> f32 {
+    aspect * (fov.to_radians() / 2.0).tan()
+}
+
+// Altura de los ojos de pie y agachado (en celdas; 0.5 = mitad de la pared)
+const EYE_HEIGHT: f32 = 0.5;
+const CROUCH_EYE_HEIGHT: f32 = 0.3;
+// Segundos que tarda en agacharse o levantarse
+const CROUCH_TIME: f32 = 0.15;
+// Salto: velocidad inicial (celdas/s) y gravedad (celdas/s²); sube unas 0.2 celdas
+const JUMP_SPEED: f32 = 1.6;
+const GRAVITY: f32 = 6.0;
+// Cuánto puede subir o bajar la vista, en fracción del alto de la pantalla
+const PITCH_LIMIT: f32 = 0.35;
+// Celdas recorridas por cada paso (una pisada)
+const STRIDE_LENGTH: f32 = 1.0;
+// Cuánto baja la cámara al pisar, en celdas
+const BOB_HEIGHT: f32 = 0.025;
+// Segundos que tarda el balanceo en aparecer al caminar o en desaparecer al detenerse
+const BOB_FADE: f32 = 0.2;
+// Barra que hay que recuperar después de vaciarla para volver a correr
+const WINDED_UNTIL: f32 = 0.25;
+// Radio mínimo para las colisiones, en celdas
+const MIN_RADIUS: f32 = 0.01;
+
+/// Parámetros del movimiento. Cada nivel puede cambiarlos en `[meta]` con `move <nombre> = valor`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MovementConfig {
+    pub speed: f32, // velocidad caminando, celdas/s
+    pub sprint: f32, // velocidad corriendo
+    pub crouch: f32, // velocidad agachado
+    pub accel: f32, // celdas/s² hacia la velocidad pedida
+    pub friction: f32, // frenado sin teclas, fracción de la velocidad por segundo
+    pub stamina: f32, // segundos que se puede correr con la barra llena
+    pub recovery: f32, // segundos de carrera que se recuperan por segundo sin correr
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self { speed: 2.5, sprint: 4.0, crouch: 1.2, accel: 20.0, friction: 10.0, stamina: 3.0, recovery: 0.75 }
+    }
+}
+
+impl MovementConfig {
+    pub fn params(&self) -> [(&'static str, f32); 7] {
+        [
+            ("speed", self.speed),
+            ("sprint", self.sprint),
+            ("crouch", self.crouch),
+            ("accel", self.accel),
+            ("friction", self.friction),
+            ("stamina", self.stamina),
+            ("recovery", self.recovery),
+        ]
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let field = match name {
+            "speed" => &mut self.speed,
+            "sprint" => &mut self.sprint,
+            "crouch" => &mut self.crouch,
+            "accel" => &mut self.accel,
+            "friction" => &mut self.friction,
+            "stamina" => &mut self.stamina,
+            "recovery" => &mut self.recovery,
+            _ => return Err(format!("parámetro de movimiento desconocido: {name}")),
+        };
+        *field = value.parse::<f32>()
+            .ok()
+            .filter(|v| v.is_finite() && *v >= 0.0)
+            .ok_or_else(|| format!("valor inválido para move {name}: {value}"))?;
+        Ok(())
+    }
+}
+
+/// Objetos que lleva el jugador. Por ahora solo llaves, que no se gastan al abrir.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Inventory {
+    keys: BTreeSet<KeyColor>,
+}
+
+impl Inventory {
+    pub fn add_key(&mut self, color: KeyColor) {
+        self.keys.insert(color);
+    }
+
+    pub fn has_key(&self, color: KeyColor) -> bool {
+        self.keys.contains(&color)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = KeyColor> + '_ {
+        self.keys.iter().copied()
+    }
+}
 
 pub struct Player {
     pub pos: Vec2,
     pub dir: Vec2,    // dirección de mirada
-    pub plane: Vec2,  // vector cámara (FOV ~66°)
+    pub plane: Vec2,  // vector cámara, perpendicular a `dir` (ver `set_fov`)
     pub radius: f32,
+    pub inventory: Inventory,
+    pub velocity: Vec2,
+    pub stamina: f32, // 0..1 de la barra de carrera
+    // Lo que pide el jugador este cuadro; `step` lo aplica
+    pub sprint: bool,
+    pub crouch: bool,
+    pub pitch: f32, // inclinación de la vista: desplazamiento del horizonte en fracción del alto (+ arriba)
+    pub jump: f32, // altura sobre el piso durante un salto, en celdas
+    vz: f32, // velocidad vertical del salto
+    pub distance: f32, // celdas recorridas caminando en total
+    stride: f32, // 0..1 del paso actual; al completarlo se pisa
+    bob: f32, // 0..1 de balanceo de la cámara, según si camina
+    crouched: f32, // 0 de pie, 1 agachado del todo
+    winded: bool, // vació la barra y todavía no recupera `WINDED_UNTIL`
 }
 
 impl Player {
@@ -13,8 +133,83 @@ impl Player {
         Self {
             pos: Vec2::new(px, py),
             dir: Vec2::new(1.0, 0.0),
-            plane: Vec2::new(0.0, 0.66),
+            plane: Vec2::new(0.0, plane_length(DEFAULT_FOV, DEFAULT_ASPECT)),
             radius: 0.2,
+            inventory: Inventory::default(),
+            velocity: Vec2::ZERO,
+            stamina: 1.0,
+            sprint: false,
+            crouch: false,
+            pitch: 0.0,
+            jump: 0.0,
+            vz: 0.0,
+            distance: 0.0,
+            stride: 0.0,
+            bob: 0.0,
+            crouched: 0.0,
+            winded: false,
+        }
+    }
+
+    // Jugador en el centro de la celda de aparición, mirando hacia su orientación
+    pub fn from_spawn(spawn: &Spawn) -> Self {
+        let dir = spawn.facing.dir();
+        let pos = spawn.pos();
+        Self {
+            dir,
+            plane: Vec2::new(-dir.y, dir.x) * plane_length(DEFAULT_FOV, DEFAULT_ASPECT),
+            ..Self::new(pos.x, pos.y)
+        }
+    }
+
+    /// Ajusta el campo de visión vertical (en grados, entre 30 y 120) para una pantalla con
+    /// proporción `aspect` (ancho / alto). El horizontal sale de la proporción, así que una
+    /// pantalla más ancha ve más a los lados sin deformar la imagen.
+    pub fn set_fov(&mut self, degrees: f32, aspect: f32) {
+        let length = plane_length(degrees.clamp(30.0, 120.0), aspect);
+        self.plane = Vec2::new(-self.dir.y, self.dir.x) * length;
+    }
+
+    /// Cámara a la altura de los ojos (más baja al agacharse, más alta en el salto).
+    pub fn camera(&self) -> Camera {
+        Camera {
+            pos: self.pos,
+            dir: self.dir,
+            plane: self.plane,
+            height: EYE_HEIGHT + (CROUCH_EYE_HEIGHT - EYE_HEIGHT) * self.crouched + self.jump + self.head_bob(),
+            pitch: self.pitch,
+        }
+    }
+
+    /// Fase del paso actual, de 0 (recién pisó) a 1 (a punto de pisar).
+    pub fn stride_phase(&self) -> f32 {
+        self.stride
+    }
+
+    // Desplazamiento vertical de la cámara al caminar: más abajo justo al pisar
+    fn head_bob(&self) -> f32 {
+        -BOB_HEIGHT * self.bob * (1.0 + (self.stride * std::f32::consts::TAU).cos()) / 2.0
+    }
+
+    /// Inclina la vista hacia arriba (positivo) o abajo, dentro de un límite.
+    pub fn look(&mut self, amount: f32) {
+        self.pitch = (self.pitch + amount).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+    }
+
+    /// Empieza un salto si está en el piso y de pie. `step` lo anima.
+    pub fn jump(&mut self) {
+        if self.jump == 0.0 && !self.crouch {
+            self.vz = JUMP_SPEED;
+        }
+    }
+
+    /// Mueve al jugador al centro de la celda; con `facing` también lo gira, conservando el FOV.
+    pub fn teleport(&mut self, (x, y): (i32, i32), facing: Option<Facing>) {
+        self.pos = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+        self.velocity = Vec2::ZERO;
+        if let Some(facing) = facing {
+            self.dir = facing.dir();
+            self.plane = Vec2::new(-self.dir.y, self.dir.x) * self.plane.length();
         }
     }
 
@@ -26,28 +221,201 @@ impl Player {
         self.dir = nd; self.plane = np;
     }
 
-    pub fn try_move(map: &Map, pos: glam::Vec2, delta: glam::Vec2, radius: f32) -> glam::Vec2 {
+    /// Mueve un círculo de radio `radius` desde `pos` y lo saca de cada celda sólida vecina
+    /// que toque, así que al chocar en ángulo se desliza a lo largo de la pared. El movimiento
+    /// se parte en pasos de a lo más medio radio para no atravesar paredes con `dt` grande.
+    pub fn try_move(map: &Map, pos: Vec2, delta: Vec2, radius: f32) -> Vec2 {
+        // Con radio 0 los subpasos serían infinitos y un punto no choca con nada
+        let radius = radius.max(MIN_RADIUS);
+        let steps = (delta.length() / (radius * 0.5)).ceil().max(1.0) as usize;
+        let step = delta / steps as f32;
         let mut out = pos;
-        // X
-        let x_next = pos.x + delta.x;
-        let sign_x = delta.x.signum();
-        if !map.is_solid((x_next + sign_x * radius) as i32, pos.y as i32) {
-            out.x = x_next;
-        }
-        // Y
-        let y_next = pos.y + delta.y;
-        let sign_y = delta.y.signum();
-        if !map.is_solid(pos.x as i32, (y_next + sign_y * radius) as i32) {
-            out.y = y_next;
+        for _ in 0..steps {
+            out = Self::resolve(map, out + step, radius);
         }
         out
     }
 
-    pub fn step(&mut self, map: &Map, forward: f32, strafe: f32, dt: f32) {
-        let speed = 2.5; // m/s
-        let f = self.dir * (forward * speed * dt);
-        let s = glam::vec2(-self.dir.y, self.dir.x) * (strafe * speed * dt);
-        let delta = f + s;
-        self.pos = Self::try_move(map, self.pos, delta, self.radius);
+    // Empuja el círculo fuera de las celdas sólidas que se traslapan con él. La celda donde
+    // está el centro no cuenta, para poder salir si quedó adentro de una.
+    fn resolve(map: &Map, mut pos: Vec2, radius: f32) -> Vec2 {
+        // Unas pasadas bastan: cada empujón puede meterlo en otra celda de la esquina
+        for _ in 0..3 {
+            let center = (pos.x.floor() as i32, pos.y.floor() as i32);
+            let mut moved = false;
+            for cy in (pos.y - radius).floor() as i32..=(pos.y + radius).floor() as i32 {
+                for cx in (pos.x - radius).floor() as i32..=(pos.x + radius).floor() as i32 {
+                    if (cx, cy) == center || !map.is_solid(cx, cy) { continue; }
+                    let min = Vec2::new(cx as f32, cy as f32);
+                    let closest = pos.clamp(min, min + Vec2::ONE);
+                    let away = pos - closest;
+                    let dist = away.length();
+                    if dist < radius && dist > 0.0 {
+                        pos += away / dist * (radius - dist);
+                        moved = true;
+                    }
+                }
+            }
+            if !moved { break; }
+        }
+        pos
+    }
+
+    /// Acelera hacia la dirección pedida (`forward`/`strafe` en -1..1) con los parámetros de
+    /// movimiento del nivel, frena con fricción si no se pide nada y mueve con colisiones.
+    /// Correr (`sprint`) gasta la barra de carrera; agachado (`crouch`) no se puede correr.
+    /// Devuelve `true` si en este cuadro se completó un paso, para el sonido de pisadas: se
+    /// cuenta la distancia realmente recorrida, así que contra una pared no se pisa.
+    pub fn step(&mut self, map: &Map, forward: f32, strafe: f32, dt: f32) -> bool {
+        let cfg = &map.meta.movement;
+        if dt <= 0.0 { return false; }
+
+        if self.vz != 0.0 || self.jump > 0.0 {
+            self.vz -= GRAVITY * dt;
+            self.jump = (self.jump + self.vz * dt).max(0.0);
+            if self.jump == 0.0 { self.vz = 0.0; }
+        }
+
+        let target_crouch = if self.crouch { 1.0 } else { 0.0 };
+        let crouch_step = dt / CROUCH_TIME;
+        self.crouched += (target_crouch - self.crouched).clamp(-crouch_step, crouch_step);
+
+        // El joystick puede pedir menos que 1; las diagonales del teclado no suman más
+        let wish = (self.dir * forward + Vec2::new(-self.dir.y, self.dir.x) * strafe).clamp_length_max(1.0);
+        let moving = wish != Vec2::ZERO;
+        let sprinting = self.sprint && !self.crouch && moving && !self.winded;
+        if sprinting {
+            self.stamina = (self.stamina - dt / cfg.stamina.max(1e-3)).max(0.0);
+            self.winded = self.stamina == 0.0;
+        } else {
+            self.stamina = (self.stamina + dt * cfg.recovery / cfg.stamina.max(1e-3)).min(1.0);
+            self.winded &= self.stamina < WINDED_UNTIL;
+        }
+
+        if moving {
+            let max_speed = if self.crouch { cfg.crouch } else if sprinting { cfg.sprint } else { cfg.speed };
+            let change = wish * max_speed - self.velocity;
+            self.velocity += change.clamp_length_max(cfg.accel * dt);
+        } else {
+            self.velocity *= (1.0 - cfg.friction * dt).max(0.0);
+            if self.velocity.length_squared() < 1e-4 { self.velocity = Vec2::ZERO; }
+        }
+
+        let from = self.pos;
+        self.pos = Self::try_move(map, self.pos, self.velocity * dt, self.radius);
+        // Lo que frenó una pared no se conserva: al soltar contra un muro no sigue empujando
+        self.velocity = (self.pos - from) / dt;
+
+        // En el aire no se dan pasos
+        let on_ground = self.jump == 0.0;
+        let travelled = if on_ground { self.pos.distance(from) } else { 0.0 };
+        self.distance += travelled;
+        let walking = on_ground && travelled > 0.1 * dt; // más de 0.1 celdas/s
+        let bob_step = dt / BOB_FADE;
+        self.bob = (self.bob + if walking { bob_step } else { -bob_step }).clamp(0.0, 1.0);
+        self.stride += travelled / STRIDE_LENGTH;
+        let footstep = self.stride >= 1.0;
+        self.stride = self.stride.fract();
+        footstep
+    }
+
+    /// Recoge la llave de la celda donde está parado. Devuelve su color si había una.
+    pub fn pick_up(&mut self, map: &mut Map) -> Option<KeyColor> {
+        let key = map.take_key(self.pos.x.floor() as i32, self.pos.y.floor() as i32)?;
+        self.inventory.add_key(key);
+        Some(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const R: f32 = 0.2;
+
+    // Distancia mínima del centro a cualquier celda sólida vecina
+    fn clearance(map: &Map, pos: Vec2) -> f32 {
+        let (px, py) = (pos.x.floor() as i32, pos.y.floor() as i32);
+        (py - 1..=py + 1)
+            .flat_map(|cy| (px - 1..=px + 1).map(move |cx| (cx, cy)))
+            .filter(|&(cx, cy)| map.is_solid(cx, cy))
+            .map(|(cx, cy)| {
+                let min = Vec2::new(cx as f32, cy as f32);
+                pos.distance(pos.clamp(min, min + Vec2::ONE))
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    fn map(src: &str) -> Map {
+        Map::parse_str(src).unwrap()
+    }
+
+    #[test]
+    fn moves_freely_in_open_space() {
+        let m = map("1 1 1 1 1\n1 S 0 0 1\n1 0 0 0 1\n1 0 0 9 1\n1 1 1 1 1\n");
+        let to = Player::try_move(&m, Vec2::new(1.5, 1.5), Vec2::new(0.7, 0.4), R);
+        assert!(to.distance(Vec2::new(2.2, 1.9)) < 1e-5);
+    }
+
+    #[test]
+    fn zero_radius_still_moves() {
+        let m = map("1 1 1 1 1\n1 S 0 0 1\n1 0 0 9 1\n1 1 1 1 1\n");
+        let to = Player::try_move(&m, Vec2::new(1.5, 1.5), Vec2::new(0.5, 0.25), 0.0);
+        assert!(to.distance(Vec2::new(2.0, 1.75)) < 1e-4);
+        // Tampoco atraviesa paredes
+        let to = Player::try_move(&m, Vec2::new(1.5, 1.5), Vec2::new(-2.0, 0.0), 0.0);
+        assert!(!m.is_solid(to.x.floor() as i32, to.y.floor() as i32));
+    }
+
+    #[test]
+    fn slides_along_wall() {
+        let m = map("1 1 1 1 1\n1 S 0 0 1\n1 0 0 9 1\n1 1 1 1 1\n");
+        // Hacia arriba y a la derecha contra la pared de arriba: avanza en x, se queda en y
+        let to = Player::try_move(&m, Vec2::new(1.5, 1.5), Vec2::new(0.5, -0.5), R);
+        assert!((to.x - 2.0).abs() < 1e-4, "{to}");
+        assert!((to.y - (1.0 + R)).abs() < 1e-4, "{to}");
+    }
+
+    #[test]
+    fn does_not_clip_into_outer_corner() {
+        // Esquina de la pared en (2, 2) vista en diagonal desde (1, 1)
+        let m = map("1 1 1 1 1\n1 S 0 0 1\n1 0 1 0 1\n1 0 0 9 1\n1 1 1 1 1\n");
+        let mut pos = Vec2::new(1.5, 1.5);
+        for _ in 0..20 {
+            pos = Player::try_move(&m, pos, Vec2::new(0.05, 0.05), R);
+            assert!(clearance(&m, pos) >= R - 1e-4, "{pos}");
+        }
+        assert!(!m.is_solid(pos.x.floor() as i32, pos.y.floor() as i32));
+    }
+
+    #[test]
+    fn cannot_squeeze_through_diagonal_gap() {
+        // (1, 1) y (2, 2) libres, unidas solo por la esquina entre dos paredes
+        let m = map("1 1 1 1\n1 S 1 1\n1 1 9 1\n1 1 1 1\n");
+        let mut pos = Vec2::new(1.5, 1.5);
+        for _ in 0..40 {
+            pos = Player::try_move(&m, pos, Vec2::new(0.05, 0.05), R);
+        }
+        assert_eq!((pos.x.floor() as i32, pos.y.floor() as i32), (1, 1), "{pos}");
+        assert!(clearance(&m, pos) >= R - 1e-4);
+    }
+
+    #[test]
+    fn no_tunneling_with_large_steps() {
+        // Un cuadro muy largo (p. ej. 4 FPS) no atraviesa una pared de una celda
+        let m = map("1 1 1 1 1\n1 S 1 9 1\n1 1 1 1 1\n");
+        let to = Player::try_move(&m, Vec2::new(1.5, 1.5), Vec2::new(2.0, 0.0), R);
+        assert!(to.x <= 2.0 - R + 1e-4, "{to}");
+    }
+
+    #[test]
+    fn step_stays_out_of_walls() {
+        let m = map("1 1 1 1 1\n1 S 0 0 1\n1 0 1 0 1\n1 0 0 9 1\n1 1 1 1 1\n");
+        let mut p = Player::from_spawn(&m.spawn);
+        p.rotate(0.6);
+        for _ in 0..200 {
+            p.step(&m, 1.0, 0.3, 1.0 / 30.0);
+            assert!(clearance(&m, p.pos) >= R - 1e-4, "{}", p.pos);
+        }
     }
 }
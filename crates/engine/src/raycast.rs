@@ -1,5 +1,6 @@
 use glam::Vec2;
 use crate::Map;
+use crate::map::KeyColor;
 
 pub struct ColumnHit {
     pub x: usize,
@@ -9,63 +10,155 @@ pub struct ColumnHit {
     pub perp: f32,
     pub tex_u: f32,
     pub side: u8, // 0 x, 1 y
+    pub door: bool, // la columna es una puerta (wall = 0)
+    pub lock: Option<KeyColor>, // cerrojo de la puerta, si tiene
 }
 
-pub fn cast_frame(
-    w: usize, h: usize,
-    pos: Vec2, dir: Vec2, plane: Vec2,
-    map: &Map
-) -> Vec<ColumnHit> {
-    let mut out = Vec::with_capacity(w);
-    for x in 0..w {
-        let camera_x = 2.0 * x as f32 / w as f32 - 1.0;
-        let ray_dir = Vec2::new(dir.x + plane.x * camera_x, dir.y + plane.y * camera_x);
+/// Punto de vista desde el que se dibuja un cuadro.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    pub pos: Vec2,
+    pub dir: Vec2,
+    pub plane: Vec2,
+    pub height: f32, // altura de los ojos en celdas (0.5 = mitad de la pared)
+    pub pitch: f32, // desplazamiento del horizonte en fracción del alto de la pantalla (+ arriba)
+}
 
-        let mut map_x = pos.x.floor() as i32;
-        let mut map_y = pos.y.floor() as i32;
+impl Camera {
+    /// Fila del horizonte en una pantalla de `h` píxeles. Mirar arriba o abajo no gira la
+    /// cámara: desplaza toda la imagen en vertical (y-shearing).
+    pub fn horizon(&self, h: usize) -> f32 {
+        h as f32 * (0.5 + self.pitch)
+    }
 
+    /// Píxeles que mide un objeto de una celda a distancia 1 en una pantalla de `w` de ancho.
+    /// Sale del largo del plano, así que los píxeles son cuadrados con cualquier proporción.
+    pub fn focal(&self, w: usize) -> f32 {
+        w as f32 / (2.0 * self.plane.length())
+    }
+}
+
+/// Recorrido DDA de las celdas que cruza un rayo que sale de `pos`, en orden.
+pub struct Dda {
+    pub cell: (i32, i32),
+    pub step: (i32, i32),
+    delta: Vec2, // avance del rayo (en múltiplos de `ray_dir`) para cruzar una celda en x / y
+    side_dist: Vec2, // hasta el siguiente borde en x / y
+}
+
+impl Dda {
+    pub fn new(pos: Vec2, ray_dir: Vec2) -> Self {
+        let cell = (pos.x.floor() as i32, pos.y.floor() as i32);
         let delta = glam::vec2(
             if ray_dir.x == 0.0 { f32::INFINITY } else { (1.0 / ray_dir.x).abs() },
             if ray_dir.y == 0.0 { f32::INFINITY } else { (1.0 / ray_dir.y).abs() },
         );
-        let (step_x, mut side_dist_x) = if ray_dir.x < 0.0 {
-            (-1, (pos.x - map_x as f32) * delta.x)
+        let (step_x, side_dist_x) = if ray_dir.x < 0.0 {
+            (-1, (pos.x - cell.0 as f32) * delta.x)
         } else {
-            ( 1, (map_x as f32 + 1.0 - pos.x) * delta.x)
+            ( 1, (cell.0 as f32 + 1.0 - pos.x) * delta.x)
         };
-        let (step_y, mut side_dist_y) = if ray_dir.y < 0.0 {
-            (-1, (pos.y - map_y as f32) * delta.y)
+        let (step_y, side_dist_y) = if ray_dir.y < 0.0 {
+            (-1, (pos.y - cell.1 as f32) * delta.y)
         } else {
-            ( 1, (map_y as f32 + 1.0 - pos.y) * delta.y)
+            ( 1, (cell.1 as f32 + 1.0 - pos.y) * delta.y)
         };
+        Self { cell, step: (step_x, step_y), delta, side_dist: Vec2::new(side_dist_x, side_dist_y) }
+    }
+
+    /// Distancia, en múltiplos de `ray_dir`, hasta el borde de la siguiente celda.
+    pub fn next_t(&self) -> f32 {
+        self.side_dist.x.min(self.side_dist.y)
+    }
+
+    /// Pasa a la siguiente celda y devuelve el lado cruzado (0 x, 1 y).
+    pub fn advance(&mut self) -> u8 {
+        if self.side_dist.x < self.side_dist.y {
+            self.side_dist.x += self.delta.x;
+            self.cell.0 += self.step.0;
+            0
+        } else {
+            self.side_dist.y += self.delta.y;
+            self.cell.1 += self.step.1;
+            1
+        }
+    }
+}
 
+pub fn cast_frame(w: usize, h: usize, camera: &Camera, map: &Map) -> Vec<ColumnHit> {
+    let Camera { pos, dir, plane, height, .. } = *camera;
+    let horizon = camera.horizon(h);
+    let focal = camera.focal(w);
+    let mut out = Vec::with_capacity(w);
+    for x in 0..w {
+        let camera_x = 2.0 * x as f32 / w as f32 - 1.0;
+        let ray_dir = Vec2::new(dir.x + plane.x * camera_x, dir.y + plane.y * camera_x);
+
+        let mut dda = Dda::new(pos, ray_dir);
         let mut hit_id = 0u8;
         let mut side = 0u8;
+        let mut door_hit = None;
+        let mut lock = None;
         while hit_id == 0 {
-            if side_dist_x < side_dist_y { side_dist_x += delta.x; map_x += step_x; side = 0; }
-            else                          { side_dist_y += delta.y; map_y += step_y; side = 1; }
+            side = dda.advance();
+            let (map_x, map_y) = dda.cell;
+            if let Some(door) = map.door_at(map_x, map_y) {
+                door_hit = hit_door(map, map_x, map_y, door.open, pos, ray_dir);
+                if door_hit.is_some() {
+                    lock = door.lock;
+                    break;
+                }
+                continue;
+            }
             hit_id = map.get(map_x, map_y);
         }
+        let (map_x, map_y) = dda.cell;
+        let (step_x, step_y) = dda.step;
 
-        let perp = if side==0 {
-            (map_x as f32 - pos.x + (1 - step_x) as f32 * 0.5) / ray_dir.x
-        } else {
-            (map_y as f32 - pos.y + (1 - step_y) as f32 * 0.5) / ray_dir.y
-        }.abs().max(1e-4);
-
-        let line_h = (h as f32 / perp) as i32;
-        let y0 = ((h as i32 - line_h) / 2).clamp(0, h as i32 - 1);
-        let y1 = ((h as i32 + line_h) / 2).clamp(0, h as i32 - 1);
+        let (perp, tex_u, side) = match door_hit {
+            Some(hit) => hit,
+            None => {
+                let perp = if side==0 {
+                    (map_x as f32 - pos.x + (1 - step_x) as f32 * 0.5) / ray_dir.x
+                } else {
+                    (map_y as f32 - pos.y + (1 - step_y) as f32 * 0.5) / ray_dir.y
+                }.abs().max(1e-4);
 
-        // coord de textura u (0..1)
-        let hit_x = if side==0 {
-            pos.y + perp * ray_dir.y
-        } else {
-            pos.x + perp * ray_dir.x
+                // coord de textura u (0..1)
+                let hit_x = if side==0 {
+                    pos.y + perp * ray_dir.y
+                } else {
+                    pos.x + perp * ray_dir.x
+                };
+                (perp, hit_x.fract(), side)
+            }
         };
-        let tex_u = hit_x.fract();
 
-        out.push(ColumnHit { x, y0, y1, wall: hit_id, perp, tex_u, side });
+        // La pared va del piso (a `height` bajo los ojos) al techo (a `1 - height` sobre ellos)
+        let line_h = focal / perp;
+        let y0 = ((horizon - (1.0 - height) * line_h) as i32).clamp(0, h as i32 - 1);
+        let y1 = ((horizon + height * line_h) as i32).clamp(0, h as i32 - 1);
+
+        out.push(ColumnHit { x, y0, y1, wall: hit_id, perp, tex_u, side, door: door_hit.is_some(), lock });
     }
     out
 }
+
+// Intersección del rayo con la hoja de una puerta, hundida a media celda. La parte ya
+// deslizada (`open`) deja pasar el rayo. Devuelve (distancia perpendicular, u, lado).
+fn hit_door(map: &Map, cx: i32, cy: i32, open: f32, pos: Vec2, ray_dir: Vec2) -> Option<(f32, f32, u8)> {
+    let vertical = map.door_is_vertical(cx, cy);
+    let (t, along, cell, side) = if vertical {
+        if ray_dir.x == 0.0 { return None; }
+        let t = (cx as f32 + 0.5 - pos.x) / ray_dir.x;
+        (t, pos.y + t * ray_dir.y, cy, 0)
+    } else {
+        if ray_dir.y == 0.0 { return None; }
+        let t = (cy as f32 + 0.5 - pos.y) / ray_dir.y;
+        (t, pos.x + t * ray_dir.x, cx, 1)
+    };
+    if t <= 0.0 || along.floor() as i32 != cell { return None; }
+    let u = along - cell as f32;
+    if u < open { return None; }
+    Some((t.max(1e-4), u - open, side))
+}
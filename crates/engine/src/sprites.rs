@@ -1,6 +1,36 @@
+use glam::Vec2;
+
+use crate::raycast::Camera;
+
 #[derive(Clone, Copy)]
 pub struct Sprite {
     pub x: f32,
     pub y: f32,
     pub frame: usize,
 }
+
+/// Objeto del mapa proyectado en pantalla.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Billboard {
+    pub x: i32,     // columna del centro
+    pub size: i32,  // alto en píxeles de un objeto de una celda de alto
+    pub depth: f32, // distancia perpendicular, comparable con `ColumnHit::perp`
+    pub floor: i32, // fila donde el objeto toca el piso
+}
+
+// Proyecta un punto del mapa con la misma cámara que `cast_frame`; `None` si queda detrás
+pub fn project(w: usize, h: usize, camera: &Camera, at: Vec2) -> Option<Billboard> {
+    let Camera { pos, dir, plane, height, .. } = *camera;
+    let rel = at - pos;
+    let inv_det = 1.0 / (plane.x * dir.y - dir.x * plane.y);
+    let tx = inv_det * (dir.y * rel.x - dir.x * rel.y);
+    let depth = inv_det * (-plane.y * rel.x + plane.x * rel.y);
+    if depth <= 0.05 { return None; }
+    let size = camera.focal(w) / depth;
+    Some(Billboard {
+        x: ((w as f32 / 2.0) * (1.0 + tx / depth)) as i32,
+        size: size as i32,
+        depth,
+        floor: (camera.horizon(h) + height * size) as i32,
+    })
+}
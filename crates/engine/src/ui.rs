@@ -1,61 +1,112 @@
 use crate::Map;
+use crate::map::{KeyColor, Rgb};
+use crate::player::Inventory;
 
-// Dibuja minimapa en esquina superior derecha del framebuffer RGBA
-pub fn draw_minimap_rgba(buf: &mut [u32], sw: usize, sh: usize, map: &Map, px: f32, py: f32) {
-    let scale = 8usize;
-    let mw = (map.w as usize) * scale;
-    let _mh = (map.h as usize) * scale;
-    let off_x = sw.saturating_sub(mw + 8);
-    let off_y = 8usize;
+fn rgb_u32((r, g, b): Rgb) -> u32 {
+    0xFF00_0000 | ((r as u32) << 16) | ((g as u32) << 8) | (b as u32)
+}
+
+fn fill_square(buf: &mut [u32], sw: usize, sh: usize, x0: usize, y0: usize, size: usize, c: u32) {
+    for y in y0..(y0 + size).min(sh) {
+        for x in x0..(x0 + size).min(sw) {
+            buf[y * sw + x] = c;
+        }
+    }
+}
+
+/// Dónde y cómo se dibuja la cuadrícula del mapa vista desde arriba.
+#[derive(Clone, Copy, Debug)]
+pub struct GridView {
+    pub x: usize, // esquina superior izquierda en pantalla
+    pub y: usize,
+    pub scale: usize, // píxeles por celda
+    // Paredes con el color de su id, y metas y aparición marcadas (para el editor)
+    pub detail: bool,
+}
+
+// Dibuja el mapa visto desde arriba: celdas, puertas, teletransportes y llaves
+pub fn draw_grid_rgba(buf: &mut [u32], sw: usize, sh: usize, map: &Map, view: &GridView) {
+    let GridView { x: off_x, y: off_y, scale, detail } = *view;
 
     let wall_color = 0xFF222222u32;     // Paredes normales (gris oscuro)
     let floor_color = 0xFFFF4444u32;    // Piso (rojo)
-    let player_color = 0xFF0000FFu32;   // Jugador (azul)
-    let special_color = 0xFF0000FFu32;  // Celdas 9 (verde brillante)
-    let player_size = 3usize;
+    let special_color = 0xFF0000FFu32;  // Metas (solo con detalle)
+    let spawn_color = 0xFF00C000u32;    // Aparición (solo con detalle)
+    let door_color = 0xFF8B5A2Bu32;     // Puertas (café)
+    let teleport_color = 0xFFB040FFu32; // Teletransportes (morado)
 
-
-    
     for my in 0..map.h as usize {
         for mx in 0..map.w as usize {
             let id = map.cells[my * map.w as usize + mx];
+            let (cx, cy) = (mx as i32, my as i32);
 
             // Color según tipo de celda
-            let c = if id > 0 {
+            let door = map.door_at(cx, cy);
+            let c = if let Some(color) = door.and_then(|d| d.lock) {
+                rgb_u32(color.rgb())
+            } else if door.is_some() {
+                door_color
+            } else if map.teleporter_at(cx, cy).is_some() {
+                teleport_color
+            } else if id > 0 && detail {
+                rgb_u32(map.meta.wall_colors.get(&id).copied().unwrap_or((100, 100, 100)))
+            } else if id > 0 {
                 wall_color
-            } else if id == 9 {
+            } else if detail && (cx, cy) == (map.spawn.x, map.spawn.y) {
+                spawn_color
+            } else if detail && map.is_goal(cx, cy) {
                 special_color
             } else {
                 floor_color
             };
 
             // Dibuja el píxel ampliado según escala
-            for dy in 0..scale {
-                for dx in 0..scale {
-                    let x = off_x + mx * scale + dx;
-                    let y = off_y + my * scale + dy;
-                    if x < sw && y < sh {
-                        buf[y * sw + x] = c;
-                    }
-                }
-            }
+            fill_square(buf, sw, sh, off_x + mx * scale, off_y + my * scale, scale, c);
         }
     }
 
+    // Enlaces entre teletransportes ya usados (cada par se dibuja una vez)
+    let cell_center = |x: i32, y: i32| {
+        ((off_x + x as usize * scale + scale / 2) as f32, (off_y + y as usize * scale + scale / 2) as f32)
+    };
+    for &(ax, ay) in map.teleporters.iter().filter(|(_, t)| t.discovered || detail).map(|(c, _)| c) {
+        let Some(((bx, by), _)) = map.teleport_target(ax, ay) else { continue };
+        if (bx, by) < (ax, ay) { continue; }
+        let ((x0, y0), (x1, y1)) = (cell_center(ax, ay), cell_center(bx, by));
+        let steps = (x1 - x0).abs().max((y1 - y0).abs()).max(1.0) as usize;
+        // Línea punteada para no tapar el mapa
+        for i in (0..=steps).step_by(2) {
+            let t = i as f32 / steps as f32;
+            let (x, y) = ((x0 + (x1 - x0) * t) as usize, (y0 + (y1 - y0) * t) as usize);
+            if x < sw && y < sh { buf[y * sw + x] = teleport_color; }
+        }
+    }
+
+    // Llaves: un punto del color de la llave en el centro de la celda
+    let dot = (scale / 2).max(2);
+    for (&(kx, ky), color) in &map.keys {
+        let x = off_x + kx as usize * scale + (scale - dot) / 2;
+        let y = off_y + ky as usize * scale + (scale - dot) / 2;
+        fill_square(buf, sw, sh, x, y, dot, rgb_u32(color.rgb()));
+    }
+}
+
+// Dibuja minimapa en esquina superior derecha del framebuffer RGBA
+pub fn draw_minimap_rgba(buf: &mut [u32], sw: usize, sh: usize, map: &Map, px: f32, py: f32) {
+    let scale = 8usize;
+    let mw = (map.w as usize) * scale;
+    let off_x = sw.saturating_sub(mw + 8);
+    let off_y = 8usize;
+
+    let player_color = 0xFF0000FFu32;   // Jugador (azul)
+    let player_size = 3usize;
+
+    draw_grid_rgba(buf, sw, sh, map, &GridView { x: off_x, y: off_y, scale, detail: false });
 
     // Dibujar al jugador
     let jx = off_x + (px as usize) * scale;
     let jy = off_y + (py as usize) * scale;
-
-    for dy in 0..player_size {
-        for dx in 0..player_size {
-            let x = jx + dx;
-            let y = jy + dy;
-            if x < sw && y < sh {
-                buf[y * sw + x] = player_color;
-            }
-        }
-    }
+    fill_square(buf, sw, sh, jx, jy, player_size, player_color);
 }
 
 const DIGITS: [[u8;15];10] = [
@@ -99,3 +150,79 @@ pub fn draw_fps_rgba(buf: &mut [u32], sw: usize, sh: usize, fps: u32) {
         }
     }
 }
+
+// Oscurece toda la pantalla: 0 = sin cambio, 1 = negro
+pub fn fade_rgba(buf: &mut [u32], amount: f32) {
+    let k = ((1.0 - amount.clamp(0.0, 1.0)) * 256.0) as u32;
+    for px in buf.iter_mut() {
+        let c = *px;
+        let r = (((c >> 16) & 0xFF) * k) >> 8;
+        let g = (((c >> 8) & 0xFF) * k) >> 8;
+        let b = ((c & 0xFF) * k) >> 8;
+        *px = (c & 0xFF00_0000) | (r << 16) | (g << 8) | b;
+    }
+}
+
+const KEY_ICON: [[u8; 8]; 3] = [
+    [0,1,1,1,0,0,0,0],
+    [1,0,0,1,1,1,1,1],
+    [0,1,1,1,0,1,0,1],
+];
+
+// Llaves del inventario en la esquina inferior izquierda, una junto a otra
+pub fn draw_inventory_rgba(buf: &mut [u32], sw: usize, sh: usize, inventory: &Inventory) {
+    let scale = 4usize;
+    let y0 = sh.saturating_sub(8 + 3 * scale);
+    for (i, key) in inventory.keys().enumerate() {
+        draw_key_icon(buf, sw, sh, 8 + i * 10 * scale, y0, scale, key);
+    }
+}
+
+fn draw_key_icon(buf: &mut [u32], sw: usize, sh: usize, x0: usize, y0: usize, scale: usize, key: KeyColor) {
+    let c = rgb_u32(key.rgb());
+    for (py, row) in KEY_ICON.iter().enumerate() {
+        for (px, &p) in row.iter().enumerate() {
+            if p == 1 {
+                fill_square(buf, sw, sh, x0 + px * scale, y0 + py * scale, scale, c);
+            }
+        }
+    }
+}
+
+// Flecha de pistas arriba al centro sobre un disco oscuro; `angle` es el giro respecto a la
+// vista (0 = hacia adelante, positivo a la derecha)
+pub fn draw_hint_arrow_rgba(buf: &mut [u32], sw: usize, sh: usize, angle: f32) {
+    let (cx, cy) = (sw as f32 / 2.0, 36.0);
+    let radius = 20.0f32;
+    let (dx, dy) = (angle.sin(), -angle.cos()); // dirección de la flecha en pantalla
+    for y in (cy - radius) as usize..(cy + radius) as usize {
+        for x in (cx - radius) as usize..(cx + radius) as usize {
+            if x >= sw || y >= sh { continue; }
+            let (px, py) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            if px * px + py * py > radius * radius { continue; }
+            // Coordenadas a lo largo de la flecha (u) y de costado (v)
+            let u = px * dx + py * dy;
+            let v = (px * -dy + py * dx).abs();
+            let head = (2.0..=14.0).contains(&u) && v <= (14.0 - u) * 0.7;
+            let shaft = (-12.0..2.0).contains(&u) && v <= 3.0;
+            let dark = 0xFF00_0000 | ((buf[y * sw + x] >> 2) & 0x003F_3F3F); // fondo a un cuarto de brillo
+            buf[y * sw + x] = if head || shaft { 0xFFFFD60A } else { dark };
+        }
+    }
+}
+
+// Barra de carrera abajo al centro; solo se ve mientras no está llena
+pub fn draw_stamina_rgba(buf: &mut [u32], sw: usize, sh: usize, stamina: f32) {
+    if stamina >= 1.0 { return; }
+    let (w, h) = (160usize, 6usize);
+    let x0 = sw.saturating_sub(w) / 2;
+    let y0 = sh.saturating_sub(h + 12);
+    let filled = (stamina.clamp(0.0, 1.0) * w as f32) as usize;
+    // Roja cuando queda poco
+    let color = if stamina < 0.25 { 0xFFE05A46 } else { 0xFFFFD60A };
+    for y in y0..(y0 + h).min(sh) {
+        for x in x0..(x0 + w).min(sw) {
+            buf[y * sw + x] = if x < x0 + filled { color } else { 0xFF202020 };
+        }
+    }
+}
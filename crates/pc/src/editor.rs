@@ -0,0 +1,221 @@
+use anyhow::{Result, anyhow};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use sdl2::{
+    event::Event,
+    keyboard::{Keycode, Mod, Scancode},
+    mouse::MouseButton,
+    pixels::{Color, PixelFormatEnum},
+    rect::Rect,
+    render::{Canvas, TextureCreator},
+    ttf::Font,
+    video::{Window, WindowContext},
+    EventPump,
+};
+use raycaster_engine::{
+    Map, Player,
+    editor::{Editor, Tool},
+    ui::{draw_grid_rgba, GridView},
+};
+
+use crate::settings::Settings;
+use crate::{draw_view, ASPECT, SW, SH};
+
+const HELP: &str = "1-8 pared  0 borrar  P aparición  G meta  O puerta  ESPACIO poner  CTRL+Z/Y deshacer  TAB vista  F2 guardar  ESC salir";
+
+/// Dónde se guarda el nivel: sobre el mismo archivo si era `.map`, si no en uno nuevo en
+/// `levels/` (los importados de imagen o Tiled no se sobrescriben).
+fn save_path(original: Option<&str>) -> String {
+    match original {
+        Some(path) if path.ends_with(".map") => path.to_string(),
+        _ => (1..)
+            .map(|n| format!("levels/custom_{n}.map"))
+            .find(|p| !Path::new(p).exists())
+            .unwrap(),
+    }
+}
+
+// Celda más grande que cabe en pantalla, dejando espacio abajo para el texto
+fn grid_view(map: &Map) -> GridView {
+    let scale = ((SW - 40) / map.w as usize).min((SH - 80) / map.h as usize).clamp(2, 32);
+    GridView {
+        x: (SW - scale * map.w as usize) / 2,
+        y: 10,
+        scale,
+        detail: true,
+    }
+}
+
+// Borde de dos píxeles alrededor de la celda del cursor
+fn draw_cursor(fb: &mut [u32], view: &GridView, (cx, cy): (i32, i32)) {
+    let x0 = view.x + cx as usize * view.scale;
+    let y0 = view.y + cy as usize * view.scale;
+    for y in y0..(y0 + view.scale).min(SH) {
+        for x in x0..(x0 + view.scale).min(SW) {
+            let edge = x < x0 + 2 || y < y0 + 2 || x + 2 >= x0 + view.scale || y + 2 >= y0 + view.scale;
+            if edge { fb[y * SW + x] = 0xFFFFFFFF; }
+        }
+    }
+}
+
+/// Editor de niveles: cuadrícula vista desde arriba con vista previa en primera persona.
+/// Devuelve `false` si se cerró la ventana.
+pub fn show_editor(
+    canvas: &mut Canvas<Window>,
+    texture_creator: &TextureCreator<WindowContext>,
+    font: &Font,
+    event_pump: &mut EventPump,
+    mut editor: Editor,
+    mut path: Option<String>, // archivo del que se abrió el nivel
+    settings: &Settings,
+) -> Result<bool> {
+    let mut tex = texture_creator.create_texture_streaming(PixelFormatEnum::RGBA8888, SW as u32, SH as u32).map_err(|e| anyhow!(e))?;
+    let mut fb = vec![0u32; SW * SH];
+    let mut status = path.clone().unwrap_or_else(|| "Nivel nuevo".into());
+    // Vista previa: copia del mapa para que las puertas se abran sin tocar el original
+    let mut preview: Option<(Map, Player)> = None;
+    let mut confirm_exit = false;
+    let mut last = Instant::now();
+
+    // El ratón pinta sobre la cuadrícula, así que se suelta mientras se edita
+    canvas.window().subsystem().sdl().mouse().set_relative_mouse_mode(false);
+
+    let result = 'editor: loop {
+        let now = Instant::now();
+        let dt = (now - last).as_secs_f32();
+        last = now;
+        let view = grid_view(&editor.map);
+
+        for event in event_pump.poll_iter() {
+            if preview.is_some() {
+                match event {
+                    Event::Quit { .. } => break 'editor false,
+                    Event::KeyDown { keycode: Some(Keycode::Tab | Keycode::Escape), .. } => preview = None,
+                    _ => {}
+                }
+                continue;
+            }
+
+            let cell_at = |x: i32, y: i32| {
+                let (cx, cy) = ((x - view.x as i32).div_euclid(view.scale as i32), (y - view.y as i32).div_euclid(view.scale as i32));
+                (cx >= 0 && cy >= 0 && cx < editor.map.w && cy < editor.map.h).then_some((cx, cy))
+            };
+            // Clic izquierdo pinta con la herramienta, clic derecho borra; arrastrar sigue pintando
+            let paint = match event {
+                Event::MouseButtonDown { mouse_btn: MouseButton::Left, x, y, .. } => cell_at(x, y).map(|c| (c, false)),
+                Event::MouseButtonDown { mouse_btn: MouseButton::Right, x, y, .. } => cell_at(x, y).map(|c| (c, true)),
+                Event::MouseMotion { mousestate, x, y, .. } if mousestate.left() || mousestate.right() => {
+                    cell_at(x, y).filter(|&c| c != editor.cursor).map(|c| (c, mousestate.right()))
+                }
+                _ => None,
+            };
+            if let Some((cell, erase)) = paint {
+                editor.cursor = cell;
+                let tool = editor.tool;
+                if erase { editor.tool = Tool::Erase; }
+                editor.apply();
+                editor.tool = tool;
+                continue;
+            }
+
+            let Event::KeyDown { keycode: Some(key), keymod, .. } = event else {
+                if let Event::Quit { .. } = event { break 'editor false; }
+                continue;
+            };
+            let ctrl = keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD);
+            if key != Keycode::Escape { confirm_exit = false; }
+            match key {
+                Keycode::F2 | Keycode::S if key == Keycode::F2 || ctrl => {
+                    let target = save_path(path.as_deref());
+                    status = match editor.map.save_to_file(&target) {
+                        Ok(()) => {
+                            editor.dirty = false;
+                            // Se guarda igual, pero avisa si el nivel todavía no se puede jugar
+                            match editor.map.validate().into_iter().find(|d| d.is_error()) {
+                                Some(d) => format!("Guardado en {target} ({d})"),
+                                None => format!("Guardado en {target}"),
+                            }
+                        }
+                        Err(e) => format!("Error guardando {target}: {e}"),
+                    };
+                    path = Some(target);
+                }
+                Keycode::Escape if editor.dirty && !confirm_exit => {
+                    status = "Hay cambios sin guardar: ESC otra vez para salir".into();
+                    confirm_exit = true;
+                }
+                Keycode::Escape => break 'editor true,
+                Keycode::Up => editor.move_cursor(0, -1),
+                Keycode::Down => editor.move_cursor(0, 1),
+                Keycode::Left => editor.move_cursor(-1, 0),
+                Keycode::Right => editor.move_cursor(1, 0),
+                Keycode::Space | Keycode::Return => { editor.apply(); }
+                Keycode::Z if ctrl => { editor.undo(); }
+                Keycode::Y if ctrl => { editor.redo(); }
+                Keycode::Num0 => editor.tool = Tool::Erase,
+                Keycode::P => editor.tool = Tool::Spawn,
+                Keycode::G => editor.tool = Tool::Goal,
+                Keycode::O => editor.tool = Tool::Door,
+                Keycode::Tab => {
+                    let mut player = Player::from_spawn(&editor.map.spawn);
+                    player.set_fov(settings.fov, ASPECT);
+                    preview = Some((editor.map.clone(), player));
+                }
+                _ => {
+                    // Números del 1 al 8: id de pared (9 es la meta en el formato de texto)
+                    let id = key as i32 - Keycode::Num0 as i32;
+                    if (1..=8).contains(&id) { editor.tool = Tool::Wall(id as u8); }
+                }
+            }
+        }
+
+        if let Some((map, player)) = preview.as_mut() {
+            let kb = event_pump.keyboard_state();
+            let axis = |pos: Scancode, neg: Scancode| {
+                f32::from(u8::from(kb.is_scancode_pressed(pos))) - f32::from(u8::from(kb.is_scancode_pressed(neg)))
+            };
+            player.rotate(axis(Scancode::Right, Scancode::Left) * 1.8 * dt);
+            map.update_doors(dt, player);
+            player.step(map, axis(Scancode::W, Scancode::S), axis(Scancode::D, Scancode::A), dt);
+            draw_view(&mut fb, map, player);
+        } else {
+            fb.fill(0xFF101010);
+            draw_grid_rgba(&mut fb, SW, SH, &editor.map, &view);
+            draw_cursor(&mut fb, &view, editor.cursor);
+        }
+
+        tex.with_lock(None, |bytes, pitch| {
+            for y in 0..SH {
+                let src = &fb[y * SW..(y + 1) * SW];
+                let dst = &mut bytes[y * pitch..y * pitch + SW * 4];
+                for (i, px) in src.iter().enumerate() {
+                    dst[i * 4..i * 4 + 4].copy_from_slice(&px.to_be_bytes());
+                }
+            }
+        }).map_err(|e| anyhow!(e))?;
+        canvas.clear();
+        canvas.copy(&tex, None, None).map_err(|e| anyhow!(e))?;
+
+        // Estado y ayuda a media escala al pie de la pantalla
+        let lines = if preview.is_some() {
+            vec!["Vista previa: WASD mover, flechas girar, TAB volver".to_string()]
+        } else {
+            let (x, y) = editor.cursor;
+            let unsaved = if editor.dirty { " *" } else { "" };
+            vec![format!("{}  ({x}, {y})  {status}{unsaved}", editor.tool.name()), HELP.to_string()]
+        };
+        for (i, line) in lines.iter().enumerate() {
+            let surface = font.render(line).blended(Color::RGB(255, 255, 255)).map_err(|e| anyhow!(e))?;
+            let texture = texture_creator.create_texture_from_surface(&surface).map_err(|e| anyhow!(e))?;
+            let q = texture.query();
+            let (w, h) = ((q.width / 2).min(SW as u32 - 20), q.height / 2);
+            canvas.copy(&texture, None, Some(Rect::new(10, SH as i32 - 50 + i as i32 * 22, w, h))).map_err(|e| anyhow!(e))?;
+        }
+        canvas.present();
+
+        std::thread::sleep(Duration::from_millis(16));
+    };
+
+    canvas.window().subsystem().sdl().mouse().set_relative_mouse_mode(true);
+    Ok(result)
+}
@@ -2,7 +2,7 @@
 
 use sdl2::{
     Sdl,
-    controller::{Axis, GameController},
+    controller::{Axis, Button, GameController},
     GameControllerSubsystem,
 };
 
@@ -17,6 +17,10 @@ pub struct GamepadHandler {
 pub struct GamepadState {
     pub movement: (f32, f32), // Joystick izquierdo (x, y)
     pub rotation: f32,        // Joystick derecho (x)
+    pub pitch: f32,           // Joystick derecho (y, arriba positivo)
+    pub jump: bool,           // Botón A
+    pub sprint: bool,         // Clic del joystick izquierdo
+    pub crouch: bool,         // Botón B
 }
 
 impl GamepadHandler {
@@ -40,6 +44,10 @@ impl GamepadHandler {
             state: GamepadState {
                 movement: (0.0, 0.0),
                 rotation: 0.0,
+                pitch: 0.0,
+                jump: false,
+                sprint: false,
+                crouch: false,
             },
         }
     }
@@ -51,6 +59,7 @@ impl GamepadHandler {
             let lx = controller.axis(Axis::LeftX) as f32;
             let ly = controller.axis(Axis::LeftY) as f32;
             let rx = controller.axis(Axis::RightX) as f32;
+            let ry = controller.axis(Axis::RightY) as f32;
 
             let normalize = |v: f32| {
                 if v.abs() < deadzone {
@@ -63,6 +72,10 @@ impl GamepadHandler {
             self.state = GamepadState {
                 movement: (normalize(lx), -normalize(ly)),
                 rotation: normalize(rx),
+                pitch: -normalize(ry),
+                jump: controller.button(Button::A),
+                sprint: controller.button(Button::LeftStick),
+                crouch: controller.button(Button::B),
             };
         }
     }
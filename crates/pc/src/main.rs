@@ -1,91 +1,47 @@
 // ... al inicio del archivo
 use anyhow::{Result, anyhow};
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
-use sdl2::{event::Event, keyboard::Keycode, pixels::PixelFormatEnum, rect::Rect};
+use sdl2::{event::Event, keyboard::Keycode, pixels::{Color, PixelFormatEnum}, rect::Rect};
 use sdl2::mixer::{self, InitFlag, Music, AUDIO_S16LSB, DEFAULT_CHANNELS,Chunk, Channel};
-use std::collections::HashMap;
 
 mod menu;
-use menu::{show_main_menu, show_victory_screen};
+use menu::{show_main_menu, show_settings_menu, show_victory_screen, MenuChoice, VictoryChoice, VictorySummary};
+
+mod settings;
+use settings::Settings;
+
+mod editor;
+use editor::show_editor;
 
 mod input;
 use input::gamepad::{GamepadHandler, GamepadState};
 
 use raycaster_engine::{
     Map, Player,
-    raycast::cast_frame,
+    campaign::{Campaign, Progress},
+    editor::Editor,
+    hint::{self, HintMode},
+    map::{MapWatcher, TriggerEvent},
+    mazegen,
+    raycast::{cast_frame, ColumnHit},
+    sprites,
     textures::wall_color_rgba,
-    ui::{draw_minimap_rgba, draw_fps_rgba},
+    ui::{draw_minimap_rgba, draw_fps_rgba, draw_hint_arrow_rgba, draw_inventory_rgba, draw_stamina_rgba, fade_rgba},
 };
 
 const SW: usize = 960;
 const SH: usize = 540;
-
-pub struct LevelColors {
-    pub sky: (u8, u8, u8),
-    pub floor: (u8, u8, u8),
-    pub wall_colors: HashMap<u8, (u8, u8, u8)>,
-}
-
-fn get_level_colors(level_name: &str) -> LevelColors {
-    let mut wall_colors = HashMap::new();
-
-    if level_name.contains("banana_land") {
-        wall_colors.insert(1, (0, 200, 214));
-        wall_colors.insert(2, (44, 168, 7));
-        wall_colors.insert(3, (34, 155, 163));
-        LevelColors {
-            sky: (121, 201, 104),
-            floor: (3, 134, 173),
-            wall_colors,
-        }
-    } else if level_name.contains("the_cave") {
-        wall_colors.insert(1, (87, 87, 87));
-        wall_colors.insert(2, (74, 74, 74));
-        wall_colors.insert(3, (68, 68, 68));
-        LevelColors {
-            sky: (60, 60, 60),
-            floor: (60, 60, 60),
-            wall_colors,
-        }
-    } else if level_name.contains("taylors_special") {
-        wall_colors.insert(1, (194, 126, 207));
-        wall_colors.insert(2, (207, 126, 162));
-        wall_colors.insert(3, (158, 85, 151));
-        LevelColors {
-            sky: (255, 240, 153),
-            floor: (89, 18, 102),
-            wall_colors,
-        }
-    } else if level_name.contains("deep_jungle") {
-        wall_colors.insert(1, (12, 102, 27));
-        wall_colors.insert(2, (16, 38, 54));
-        wall_colors.insert(3, (82, 82, 82));
-        LevelColors {
-            sky: (64, 11, 11),
-            floor: (18, 54, 21),
-            wall_colors,
-        }
-    } else if level_name.contains("monkey_temple") {
-        wall_colors.insert(1, (110, 110, 110));
-        wall_colors.insert(2, (78, 110, 109));
-        wall_colors.insert(3, (101, 142, 156));
-        LevelColors {
-            sky: (189, 146, 77),
-            floor: (82, 182, 82),
-            wall_colors,
-        }
-    } else {
-        wall_colors.insert(1, (170, 170, 170));
-        wall_colors.insert(2, (136, 136, 136));
-        wall_colors.insert(3, (102, 102, 102));
-        LevelColors {
-            sky: (135, 206, 235),
-            floor: (68, 68, 68),
-            wall_colors,
-        }
-    }
-}
+const CAMPAIGN_FILE: &str = "levels/campaign.txt";
+const PROGRESS_FILE: &str = "progress.txt";
+const SETTINGS_FILE: &str = "settings.txt";
+// Música de los niveles sin `music` en `[meta]` (laberintos, importados, nuevos del editor)
+const DEFAULT_MUSIC: &str = "assets/music/Tropical Adventure.mp3";
+const ASPECT: f32 = SW as f32 / SH as f32;
+// Milisegundos del audio de pasos que suenan por pisada (el archivo trae varias seguidas)
+const FOOTSTEP_MS: i32 = 350;
+// Segundos que se suman al tiempo del nivel cada vez que se encienden las pistas
+const HINT_PENALTY: f32 = 10.0;
 
 fn rgb_to_u32(r: u8, g: u8, b: u8) -> u32 {
     (0xFF << 24) | ((r as u32) << 16) | ((g as u32) << 8) | (b as u32)
@@ -100,6 +56,51 @@ fn wall_color_shaded_rgba_rgb(rgb: (u8, u8, u8), dark: bool) -> u32 {
     rgb_to_u32(r, g, b)
 }
 
+/// Cielo, piso y paredes vistos por el jugador. Devuelve las columnas para ocultar sprites.
+fn draw_view(fb: &mut [u32], map: &Map, player: &Player) -> Vec<ColumnHit> {
+    let level = &map.meta;
+    let camera = player.camera();
+    let horizon = (camera.horizon(SH) as usize).min(SH);
+    let sky = rgb_to_u32(level.sky.0, level.sky.1, level.sky.2);
+    let floor = rgb_to_u32(level.floor.0, level.floor.1, level.floor.2);
+
+    for y in 0..SH {
+        let c = if y < horizon { sky } else { floor };
+        let row = &mut fb[y * SW..(y + 1) * SW];
+        for px in row {
+            *px = c;
+        }
+    }
+
+    let cols = cast_frame(SW, SH, &camera, map);
+    for c in &cols {
+        let wall_rgb = if let Some(key) = c.lock {
+            key.rgb()
+        } else if c.door {
+            level.door
+        } else {
+            level.wall_colors.get(&c.wall).copied().unwrap_or((100, 100, 100))
+        };
+        let color = wall_color_shaded_rgba_rgb(wall_rgb, c.side == 1);
+        for y in c.y0 as usize..=c.y1 as usize {
+            fb[y * SW + c.x] = color;
+        }
+    }
+    cols
+}
+
+/// Campaña del manifiesto más los niveles sueltos de la carpeta (los del editor). Sin
+/// manifiesto se juegan todos los niveles de la carpeta en orden alfabético.
+fn load_campaign() -> Result<Campaign> {
+    let mut campaign = if std::path::Path::new(CAMPAIGN_FILE).exists() {
+        Campaign::load(CAMPAIGN_FILE)?
+    } else {
+        Campaign::from_dir("levels")?
+    };
+    campaign.add_extras("levels")?;
+    Ok(campaign)
+}
+
 fn main() -> Result<()> {
     let sdl = sdl2::init().map_err(|e| anyhow!(e))?;
     let video = sdl.video().map_err(|e| anyhow!(e))?;
@@ -125,41 +126,102 @@ fn main() -> Result<()> {
     .map_err(|e| anyhow!("Error cargando walk.wav: {e}"))?;
     let select_level_sound = Chunk::from_file("assets/sfx/selectlevel.wav")
     .map_err(|e| anyhow!("Error cargando efecto select: {e}"))?;
+    // Sonidos pedidos por los disparadores de los niveles, cargados la primera vez
+    let mut trigger_sounds: HashMap<String, Chunk> = HashMap::new();
 
-    'game: loop {
-        let selected_level = match show_main_menu(&mut canvas, &texture_creator, &font, &mut event_pump) {
-            Some(path) => {
-                Channel::all().play(&select_level_sound, 0)
-                .map_err(|e| anyhow!("Error al reproducir efecto: {e}"))?;
+    let mut campaign = load_campaign()?;
+    let mut progress = Progress::load(PROGRESS_FILE);
+    let mut settings = Settings::load(SETTINGS_FILE);
+    // "Siguiente nivel" en la pantalla de victoria salta el menú
+    let mut next_choice: Option<MenuChoice> = None;
 
-                path
+    'game: loop {
+        let choice = match next_choice.take() {
+            Some(choice) => choice,
+            None => match show_main_menu(&mut canvas, &texture_creator, &font, &mut event_pump, &campaign, &progress) {
+                Some(choice) => {
+                    Channel::all().play(&select_level_sound, 0)
+                    .map_err(|e| anyhow!("Error al reproducir efecto: {e}"))?;
+
+                    choice
+                },
+                None => return Ok(()),
             },
-            None => return Ok(()),
         };
-
-        let level_colors = get_level_colors(&selected_level);
-
-        let music_path = if selected_level.contains("banana_land") {
-            "assets/music/Jungle.mp3"
-        } else if selected_level.contains("deep_jungle") {
-            "assets/music/monkeys.mp3"
-        } else if selected_level.contains("the_cave") {
-            "assets/music/Tropical Adventure.mp3"
-        } else if selected_level.contains("taylors_special") {
-            "assets/music/shakeitoff.mp3"
-        } else {
-            "assets/music/Jungle.mp3"
+        let level_index = match choice {
+            MenuChoice::Level(i) => Some(i),
+            MenuChoice::Random(_) => None,
+            MenuChoice::Settings => {
+                show_settings_menu(&mut canvas, &texture_creator, &font, &mut event_pump, &mut settings, ASPECT);
+                if let Err(e) = settings.save(SETTINGS_FILE) {
+                    eprintln!("Error guardando las opciones: {e}");
+                }
+                continue 'game;
+            }
+            MenuChoice::Edit(level) => {
+                let (editor, path) = match level {
+                    None => (Editor::blank(16, 12), None),
+                    Some(i) => {
+                        let path = format!("levels/{}", campaign.levels[i].file);
+                        match Map::load_from_file(&path) {
+                            Ok(map) => (Editor::new(map), Some(path)),
+                            Err(e) => {
+                                eprintln!("{e}");
+                                continue 'game;
+                            }
+                        }
+                    }
+                };
+                if show_editor(&mut canvas, &texture_creator, &font, &mut event_pump, editor, path, &settings)? {
+                    // El editor puede haber guardado un nivel nuevo en la carpeta
+                    campaign = load_campaign()?;
+                    continue 'game;
+                }
+                break 'game;
+            }
         };
 
-        
+        // Los niveles de archivo se vigilan para recargarlos mientras se juega
+        let (mut map, mut watcher) = match choice {
+            MenuChoice::Random(cfg) => (mazegen::generate(&cfg), None),
+            MenuChoice::Edit(_) | MenuChoice::Settings => unreachable!(),
+            // Si el archivo cambió y ya no carga, volvemos al menú (que muestra el error)
+            MenuChoice::Level(i) => {
+                let path = format!("levels/{}", campaign.levels[i].file);
+                match Map::load_from_file(&path) {
+                    Ok(map) => (map, Some(MapWatcher::new(&path))),
+                    Err(e) => {
+                        eprintln!("{e}");
+                        continue 'game;
+                    }
+                }
+            }
+        };
+        let mut reload_error: Option<String> = None;
+        let music_path = map.meta.music.as_deref().unwrap_or(DEFAULT_MUSIC);
 
+        // Sin la música se juega igual: un archivo que falta no cierra el juego. `_music` vive
+        // hasta el final del nivel porque al soltarla se corta
         mixer::Music::halt();
-        let music = Music::from_file(music_path).map_err(|e| anyhow!(e))?;
-        music.play(-1).map_err(|e| anyhow!(e))?;
-
-        let map = Map::load_from_file(&selected_level)?;
-        let mut player = Player::new(2.5, 2.5);
+        let _music = Music::from_file(music_path)
+            .and_then(|music| music.play(-1).map(|()| music))
+            .map_err(|e| eprintln!("Error con la música {music_path}: {e}"))
+            .ok();
+
+        let spawn_player = |map: &Map| {
+            let mut player = Player::from_spawn(&map.spawn);
+            player.set_fov(settings.fov, ASPECT);
+            player
+        };
+        let mut player = spawn_player(&map);
         let mut fb = vec![0u32; SW * SH];
+        // Celda del cuadro anterior: solo se teletransporta al entrar, no al llegar
+        let mut last_cell = (map.spawn.x, map.spawn.y);
+        let mut fade = 0.0f32;
+        let level_start = Instant::now();
+        let mut message: Option<(String, Instant)> = None;
+        let mut hint_mode = HintMode::Off;
+        let mut hint_penalty = 0.0f32;
 
         let mut last = Instant::now();
         let mut fps_timer = Instant::now();
@@ -174,11 +236,41 @@ fn main() -> Result<()> {
             for e in event_pump.poll_iter() {
                 match e {
                     Event::Quit { .. } | Event::KeyDown { keycode: Some(Keycode::Escape), .. } => break 'game,
-                    Event::MouseMotion { xrel, .. } => player.rotate((xrel as f32) * 0.003),
+                    Event::MouseMotion { xrel, yrel, .. } => {
+                        player.rotate((xrel as f32) * 0.003);
+                        player.look(-(yrel as f32) * 0.002);
+                    }
+                    Event::KeyDown { keycode: Some(Keycode::Space), repeat: false, .. } => player.jump(),
+                    Event::KeyDown { keycode: Some(Keycode::E), .. } => { map.interact(&player); }
+                    Event::KeyDown { keycode: Some(Keycode::H), repeat: false, .. } => {
+                        if hint_mode == HintMode::Off { hint_penalty += HINT_PENALTY; }
+                        hint_mode = hint_mode.next();
+                    }
                     _ => {}
                 }
             }
 
+            // Recarga en caliente: si el archivo nuevo está roto se sigue jugando el anterior y
+            // se muestra el error; el jugador se queda donde está si la celda sigue libre y lo
+            // que ya pasó en el nivel (disparadores, llaves, puertas) se conserva
+            if let Some(result) = watcher.as_mut().and_then(MapWatcher::poll) {
+                match result {
+                    Ok(mut new_map) => {
+                        new_map.carry_state_from(&map, &player.inventory);
+                        map = new_map;
+                        if map.is_solid(player.pos.x.floor() as i32, player.pos.y.floor() as i32) {
+                            player = spawn_player(&map);
+                        }
+                        last_cell = (player.pos.x.floor() as i32, player.pos.y.floor() as i32);
+                        reload_error = None;
+                    }
+                    Err(e) => {
+                        eprintln!("{e}");
+                        reload_error = Some(e.to_string());
+                    }
+                }
+            }
+
             let gpad = &mut gamepad;
             gpad.update();
             let state = gpad.state();
@@ -188,11 +280,15 @@ fn main() -> Result<()> {
             let mut forward = 0.0;
             let mut strafe = 0.0;
 
-            let mut moved_keyboard = false;
-            if kb.is_scancode_pressed(sdl2::keyboard::Scancode::W) { forward += 1.0; moved_keyboard = true; }
-            if kb.is_scancode_pressed(sdl2::keyboard::Scancode::S) { forward -= 1.0; moved_keyboard = true; }
-            if kb.is_scancode_pressed(sdl2::keyboard::Scancode::A) { strafe -= 1.0; moved_keyboard = true; }
-            if kb.is_scancode_pressed(sdl2::keyboard::Scancode::D) { strafe += 1.0; moved_keyboard = true; }
+            if kb.is_scancode_pressed(sdl2::keyboard::Scancode::W) { forward += 1.0; }
+            if kb.is_scancode_pressed(sdl2::keyboard::Scancode::S) { forward -= 1.0; }
+            if kb.is_scancode_pressed(sdl2::keyboard::Scancode::A) { strafe -= 1.0; }
+            if kb.is_scancode_pressed(sdl2::keyboard::Scancode::D) { strafe += 1.0; }
+            // Shift corre, Ctrl o C se agacha
+            player.sprint = kb.is_scancode_pressed(sdl2::keyboard::Scancode::LShift) || state.sprint;
+            player.crouch = kb.is_scancode_pressed(sdl2::keyboard::Scancode::LCtrl)
+                || kb.is_scancode_pressed(sdl2::keyboard::Scancode::C)
+                || state.crouch;
 
             // Suma el movimiento del joystick izquierdo
             let move_x = state.movement.0;
@@ -200,16 +296,50 @@ fn main() -> Result<()> {
             forward += move_y;
             strafe += move_x;
 
-            let moved_gamepad = move_x.abs() > 0.05 || move_y.abs() > 0.05;
+            // Puertas: se abren solas al acercarse o con E
+            map.update_doors(dt, &player);
 
-            // Si se movió por teclado o joystick, reproducir sonido
-            if (moved_keyboard || moved_gamepad) && !Channel(1).is_playing() {
-                Channel(1).play(&walk_sound, 0)
+            // Aplica movimiento con colisiones; una pisada por cada paso realmente recorrido
+            if player.step(&map, forward, strafe, dt) {
+                Channel(1).play_timed(&walk_sound, 0, FOOTSTEP_MS)
                     .map_err(|e| anyhow!("Error reproduciendo walk.wav: {e}"))?;
             }
 
-            // Aplica movimiento con colisiones
-            player.step(&map, forward, strafe, dt);
+            let cell = (player.pos.x.floor() as i32, player.pos.y.floor() as i32);
+            if cell != last_cell {
+                if let Some((target, facing)) = map.teleport_target(cell.0, cell.1) {
+                    map.discover_teleporter(cell.0, cell.1);
+                    player.teleport(target, facing);
+                    fade = 1.0;
+                }
+            }
+            last_cell = (player.pos.x.floor() as i32, player.pos.y.floor() as i32);
+            fade = (fade - dt / 0.4).max(0.0);
+
+            let picked = player.pick_up(&mut map);
+            if picked.is_some() {
+                Channel::all().play(&select_level_sound, 0)
+                    .map_err(|e| anyhow!("Error reproduciendo efecto: {e}"))?;
+            }
+
+            // Disparadores del nivel
+            let mut level_ended = false;
+            for event in map.update_triggers(level_start.elapsed().as_secs_f32(), &player, picked) {
+                match event {
+                    TriggerEvent::Sound(path) => {
+                        if !trigger_sounds.contains_key(&path) {
+                            match Chunk::from_file(&path) {
+                                Ok(chunk) => { trigger_sounds.insert(path.clone(), chunk); }
+                                Err(e) => { eprintln!("Error cargando {path}: {e}"); continue; }
+                            }
+                        }
+                        Channel::all().play(&trigger_sounds[&path], 0)
+                            .map_err(|e| anyhow!("Error reproduciendo {path}: {e}"))?;
+                    }
+                    TriggerEvent::Message(text) => message = Some((text, Instant::now())),
+                    TriggerEvent::EndLevel => level_ended = true,
+                }
+            }
 
 
             // Aplica rotación (joystick derecho y flechas)
@@ -218,48 +348,98 @@ fn main() -> Result<()> {
 
             let rot = state.rotation;
             player.rotate(rot * 0.04); // Ajusta sensibilidad si es necesario
+            player.look(state.pitch * 1.2 * dt);
+            if state.jump { player.jump(); }
 
 
           
             
 
 
-            if let Some((gx, gy)) = map.goal {
-                let px = player.pos.x as i32;
-                let py = player.pos.y as i32;
-                if px == gx && py == gy {
-                    let restart = show_victory_screen(&mut canvas, &texture_creator, &font, &mut event_pump);
-                    Channel::all().play(&complete_level_sound, 0)
-                    .map_err(|e| anyhow!("Error reproduciendo win.wav: {e}"))?;
-                    if restart {
+            if level_ended || map.is_goal(player.pos.x as i32, player.pos.y as i32) {
+                let mut summary = VictorySummary {
+                    time: level_start.elapsed().as_secs_f32() + hint_penalty,
+                    hint_penalty,
+                    par: None,
+                    new_record: false,
+                    has_next: false,
+                };
+                let mut next = None;
+                if let Some(i) = level_index {
+                    let level = &campaign.levels[i];
+                    summary.new_record = progress.record(&level.file, summary.time);
+                    if let Err(e) = progress.save(PROGRESS_FILE) {
+                        eprintln!("Error guardando el progreso: {e}");
+                    }
+                    summary.par = level.par;
+                    next = campaign.next_unlocked(i, &progress);
+                    summary.has_next = next.is_some();
+                }
+                let choice = show_victory_screen(&mut canvas, &texture_creator, &font, &mut event_pump, &summary);
+                Channel::all().play(&complete_level_sound, 0)
+                .map_err(|e| anyhow!("Error reproduciendo win.wav: {e}"))?;
+                match choice {
+                    VictoryChoice::Next => {
+                        next_choice = next.map(MenuChoice::Level);
                         continue 'game;
-                    } else {
-                        break 'game;
                     }
+                    VictoryChoice::Menu => continue 'game,
+                    VictoryChoice::Quit => break 'game,
                 }
             }
 
-            let sky = rgb_to_u32(level_colors.sky.0, level_colors.sky.1, level_colors.sky.2);
-            let floor = rgb_to_u32(level_colors.floor.0, level_colors.floor.1, level_colors.floor.2);
-
-            for y in 0..SH {
-                let c = if y < SH / 2 { sky } else { floor };
-                let row = &mut fb[y * SW..(y + 1) * SW];
-                for px in row {
-                    *px = c;
+            let cols = draw_view(&mut fb, &map, &player);
+            let camera = player.camera();
+
+            // Llaves: un cuadrado apoyado en el piso, oculto por las paredes más cercanas
+            for (&(kx, ky), key) in &map.keys {
+                let at = glam::Vec2::new(kx as f32 + 0.5, ky as f32 + 0.5);
+                let Some(b) = sprites::project(SW, SH, &camera, at) else { continue };
+                let size = b.size / 4;
+                let bottom = b.floor;
+                let (r, g, bl) = key.rgb();
+                let color = rgb_to_u32(r, g, bl);
+                for x in (b.x - size / 2).max(0)..(b.x + size / 2).min(SW as i32) {
+                    if cols[x as usize].perp < b.depth { continue; }
+                    for y in (bottom - size).max(0)..bottom.min(SH as i32) {
+                        fb[y as usize * SW + x as usize] = color;
+                    }
                 }
             }
 
-            let cols = cast_frame(SW, SH, player.pos, player.dir, player.plane, &map);
-            for c in cols {
-                let wall_rgb = level_colors.wall_colors.get(&c.wall).copied().unwrap_or((100, 100, 100));
-                let color = wall_color_shaded_rgba_rgb(wall_rgb, c.side == 1);
-                for y in c.y0 as usize..=c.y1 as usize {
-                    fb[y * SW + c.x] = color;
+            // Pistas: camino más corto desde la celda actual hasta la meta más cercana
+            let hint_path = match hint_mode {
+                HintMode::Off => None,
+                _ => map.path_to_goal((player.pos.x.floor() as i32, player.pos.y.floor() as i32), &player.inventory),
+            };
+            if let (HintMode::Breadcrumbs, Some(path)) = (hint_mode, &hint_path) {
+                // Migas brillantes en el piso de las próximas celdas, latiendo con el tiempo
+                let glow = 0.75 + 0.25 * (level_start.elapsed().as_secs_f32() * 5.0).sin();
+                let color = rgb_to_u32((255.0 * glow) as u8, (214.0 * glow) as u8, (10.0 * glow) as u8);
+                for &(cx, cy) in path.iter().skip(1).take(12) {
+                    let at = glam::Vec2::new(cx as f32 + 0.5, cy as f32 + 0.5);
+                    let Some(b) = sprites::project(SW, SH, &camera, at) else { continue };
+                    let size = (b.size / 10).max(2);
+                    let bottom = b.floor - b.size / 8;
+                    for x in (b.x - size).max(0)..(b.x + size).min(SW as i32) {
+                        if cols[x as usize].perp < b.depth { continue; }
+                        for y in (bottom - size / 2).max(0)..bottom.min(SH as i32) {
+                            fb[y as usize * SW + x as usize] = color;
+                        }
+                    }
                 }
             }
 
+            if fade > 0.0 { fade_rgba(&mut fb, fade); }
+
             draw_minimap_rgba(&mut fb, SW, SH, &map, player.pos.x, player.pos.y);
+            draw_inventory_rgba(&mut fb, SW, SH, &player.inventory);
+            draw_stamina_rgba(&mut fb, SW, SH, player.stamina);
+            if let (HintMode::Arrow, Some(path)) = (hint_mode, &hint_path) {
+                if let Some(target) = hint::arrow_target(&map, player.pos, path) {
+                    draw_hint_arrow_rgba(&mut fb, SW, SH, hint::relative_angle(player.pos, player.dir, target));
+                }
+            }
             frames += 1;
             if fps_timer.elapsed() >= Duration::from_secs(1) {
                 fps = frames;
@@ -281,6 +461,31 @@ fn main() -> Result<()> {
 
             canvas.clear();
             canvas.copy(&tex, None, Some(Rect::new(0, 0, SW as u32, SH as u32))).map_err(|e| anyhow!(e))?;
+
+            // Mensaje de un disparador, centrado abajo durante unos segundos
+            if let Some((text, since)) = &message {
+                if since.elapsed() < Duration::from_secs(3) {
+                    let surface = font.render(text).blended(Color::RGB(255, 255, 255)).map_err(|e| anyhow!(e))?;
+                    let texture = texture_creator.create_texture_from_surface(&surface).map_err(|e| anyhow!(e))?;
+                    let q = texture.query();
+                    let dst = Rect::new((SW as i32 - q.width as i32) / 2, SH as i32 - q.height as i32 - 40, q.width, q.height);
+                    canvas.copy(&texture, None, Some(dst)).map_err(|e| anyhow!(e))?;
+                }
+            }
+
+            // Error de la última recarga, sobre un fondo negro arriba a la izquierda
+            if let Some(err) = &reload_error {
+                let mut y = 60;
+                for line in err.lines() {
+                    let surface = font.render(line).blended(Color::RGB(255, 90, 90)).map_err(|e| anyhow!(e))?;
+                    let texture = texture_creator.create_texture_from_surface(&surface).map_err(|e| anyhow!(e))?;
+                    let q = texture.query();
+                    canvas.set_draw_color(Color::RGB(0, 0, 0));
+                    canvas.fill_rect(Rect::new(4, y - 2, q.width + 8, q.height + 4)).map_err(|e| anyhow!(e))?;
+                    canvas.copy(&texture, None, Some(Rect::new(8, y, q.width, q.height))).map_err(|e| anyhow!(e))?;
+                    y += q.height as i32 + 6;
+                }
+            }
             canvas.present();
         }
     }
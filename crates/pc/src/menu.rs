@@ -1,4 +1,3 @@
-use std::fs;
 use std::time::Duration;
 use sdl2::{
     event::Event,
@@ -11,30 +10,83 @@ use sdl2::{
     EventPump,
 
 };
+use raycaster_engine::Map;
+use raycaster_engine::campaign::{Campaign, Progress};
+use raycaster_engine::mazegen::{Algorithm, MazeConfig};
 
-/// Menú principal: muestra niveles disponibles y permite seleccionar uno
+use crate::settings::Settings;
+
+// Filas de la lista de niveles que caben entre el título y el texto de abajo
+const MENU_ROWS: usize = 7;
+
+/// Lo que eligió el jugador en el menú principal
+pub enum MenuChoice {
+    Level(usize), // índice en la campaña
+    Random(MazeConfig),
+    Edit(Option<usize>), // nivel de la campaña, o uno nuevo
+    Settings,
+}
+
+/// Nivel listado en el menú principal
+struct LevelEntry {
+    name: String,
+    error: Option<String>, // error de carga o primer error de validación
+    locked: bool,
+    info: String, // mejor tiempo y par, o qué falta para desbloquearlo
+}
+
+fn format_time(secs: f32) -> String {
+    format!("{secs:.1} s")
+}
+
+/// Menú principal: muestra los niveles de la campaña en orden y permite seleccionar uno
 pub fn show_main_menu(
     canvas: &mut Canvas<Window>,
     texture_creator: &TextureCreator<WindowContext>,
     font: &Font,
     event_pump: &mut EventPump, // ✅ Usar referencia existente
-) -> Option<String> {
-    let levels = fs::read_dir("levels")
-        .expect("No se pudo leer el directorio levels")
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            let path = entry.path();
-            if path.is_file() && path.extension()? == "map" {
-                Some(path.file_name()?.to_str()?.to_string())
+    campaign: &Campaign,
+    progress: &Progress,
+) -> Option<MenuChoice> {
+    let levels = campaign.levels.iter()
+        .enumerate()
+        .map(|(i, level)| {
+            let (map, error) = match Map::load_from_file(&format!("levels/{}", level.file)) {
+                Ok(m) => {
+                    let error = m.validate().into_iter().find(|d| d.is_error()).map(|d| d.to_string());
+                    (Some(m), error)
+                }
+                Err(e) => (None, Some(e.to_string())),
+            };
+            // Nombre del manifiesto, si no el del encabezado [meta], si no el archivo
+            let name = level.name.clone()
+                .or_else(|| map.and_then(|m| m.meta.name))
+                .unwrap_or_else(|| level.file.clone());
+            let locked = !campaign.is_unlocked(i, progress);
+            let info = if locked {
+                "Bloqueado: completa los niveles anteriores".to_string()
             } else {
-                None
-            }
+                let best = progress.best_time(&level.file).map(format_time).unwrap_or_else(|| "-".into());
+                match level.par {
+                    Some(par) => format!("Mejor: {best}   Par: {}", format_time(par)),
+                    None => format!("Mejor: {best}"),
+                }
+            };
+            LevelEntry { name, error, locked, info }
         })
         .collect::<Vec<_>>();
 
-    let mut selected = 0;
+    let mut selected = 0usize;
+    let mut scroll = 0; // primera fila visible
 
     loop {
+        // La lista se desplaza para que la fila elegida siempre se vea
+        scroll = scroll.clamp(selected.saturating_sub(MENU_ROWS - 1), selected);
+        let row_rect = |i: usize| {
+            (scroll..scroll + MENU_ROWS).contains(&i)
+                .then(|| Rect::new(320, 180 + (i - scroll) as i32 * 40, 300, 40))
+        };
+
         canvas.set_draw_color(Color::RGB(27, 67, 50));
         canvas.clear();
 
@@ -48,18 +100,66 @@ pub fn show_main_menu(
 
         // Niveles
         for (i, level) in levels.iter().enumerate() {
-            let color = if i == selected {
+            let Some(rect) = row_rect(i) else { continue };
+            let color = if level.error.is_some() || level.locked {
+                Color::RGB(90, 90, 90) // nivel roto o bloqueado: gris
+            } else if i == selected {
                 Color::RGB(64, 145, 108)
             } else {
                 Color::RGB(202, 210, 197)
             };
-            let surface = font.render(level).blended(color).unwrap();
+            let surface = font.render(&level.name).blended(color).unwrap();
             let texture = texture_creator.create_texture_from_surface(&surface).unwrap();
-            canvas.copy(&texture, None, Some(Rect::new(320, 180 + (i as i32) * 40, 300, 40))).unwrap();
+            canvas.copy(&texture, None, Some(rect)).unwrap();
+        }
+
+        // Entrada extra: laberinto generado
+        let color = if selected == levels.len() {
+            Color::RGB(64, 145, 108)
+        } else {
+            Color::RGB(255, 214, 10)
+        };
+        let surface = font.render("Laberinto aleatorio").blended(color).unwrap();
+        let texture = texture_creator.create_texture_from_surface(&surface).unwrap();
+        if let Some(rect) = row_rect(levels.len()) { canvas.copy(&texture, None, Some(rect)).unwrap(); }
+
+        // Entrada extra: editor con un nivel nuevo (E sobre un nivel lo abre en el editor)
+        let color = if selected == levels.len() + 1 {
+            Color::RGB(64, 145, 108)
+        } else {
+            Color::RGB(255, 214, 10)
+        };
+        let surface = font.render("Editor de niveles").blended(color).unwrap();
+        let texture = texture_creator.create_texture_from_surface(&surface).unwrap();
+        if let Some(rect) = row_rect(levels.len() + 1) { canvas.copy(&texture, None, Some(rect)).unwrap(); }
+
+        // Entrada extra: opciones
+        let color = if selected == levels.len() + 2 {
+            Color::RGB(64, 145, 108)
+        } else {
+            Color::RGB(255, 214, 10)
+        };
+        let surface = font.render("Opciones").blended(color).unwrap();
+        let texture = texture_creator.create_texture_from_surface(&surface).unwrap();
+        if let Some(rect) = row_rect(levels.len() + 2) { canvas.copy(&texture, None, Some(rect)).unwrap(); }
+
+        // Error del nivel seleccionado, o su mejor tiempo
+        if let Some(level) = levels.get(selected) {
+            let (text, color) = match &level.error {
+                Some(err) => (err, Color::RGB(230, 90, 70)),
+                None => (&level.info, Color::RGB(202, 210, 197)),
+            };
+            let surface = font.render(text).blended(color).unwrap();
+            let texture = texture_creator.create_texture_from_surface(&surface).unwrap();
+            let q = texture.query();
+            // Texto a media escala, recortado al ancho de la ventana
+            let w = (q.width / 2).min(900);
+            canvas.copy(&texture, None, Some(Rect::new(30, 490, w, q.height / 2))).unwrap();
         }
 
         canvas.present();
 
+        let mut open_random = false;
         for event in event_pump.poll_iter() {
             match event {
                 Event::Quit { .. } => return None,
@@ -70,26 +170,187 @@ pub fn show_main_menu(
                     }
                 }
                 Event::KeyDown { keycode: Some(Keycode::Down), .. } => {
-                    if selected < levels.len() - 1 {
+                    if selected < levels.len() + 2 {
                         selected += 1;
                     }
                 }
-                Event::KeyDown { keycode: Some(Keycode::Return), .. } => {
-                    return Some(format!("levels/{}", levels[selected]));
+                Event::KeyDown { keycode: Some(Keycode::Return), .. } if selected == levels.len() => {
+                    open_random = true;
+                }
+                Event::KeyDown { keycode: Some(Keycode::Return), .. } if selected == levels.len() + 1 => {
+                    return Some(MenuChoice::Edit(None));
+                }
+                Event::KeyDown { keycode: Some(Keycode::Return), .. } if selected > levels.len() + 1 => {
+                    return Some(MenuChoice::Settings);
+                }
+                Event::KeyDown { keycode: Some(Keycode::E), .. } if selected < levels.len() => {
+                    return Some(MenuChoice::Edit(Some(selected)));
+                }
+                Event::KeyDown { keycode: Some(Keycode::Return), .. }
+                    if levels[selected].error.is_none() && !levels[selected].locked =>
+                {
+                    return Some(MenuChoice::Level(selected));
+                }
+                _ => {}
+            }
+        }
+
+        if open_random {
+            if let Some(cfg) = show_random_menu(canvas, texture_creator, font, event_pump) {
+                return Some(MenuChoice::Random(cfg));
+            }
+        }
+    }
+}
+
+/// Opciones del laberinto aleatorio: algoritmo, semilla y tamaño
+fn show_random_menu(
+    canvas: &mut Canvas<Window>,
+    texture_creator: &TextureCreator<WindowContext>,
+    font: &Font,
+    event_pump: &mut EventPump,
+) -> Option<MazeConfig> {
+    let mut cfg = MazeConfig {
+        seed: random_seed(),
+        width: 8,
+        height: 8,
+        algorithm: Algorithm::Backtracker,
+    };
+    let mut row = 0usize;
+
+    loop {
+        canvas.set_draw_color(Color::RGB(27, 67, 50));
+        canvas.clear();
+
+        let lines = [
+            format!("Algoritmo: < {} >", cfg.algorithm.name()),
+            format!("Semilla: < {} >", cfg.seed),
+            format!("Tamaño: < {}x{} >", cfg.width, cfg.height),
+            "ENTER jugar - R semilla nueva - ESC volver".to_string(),
+        ];
+        for (i, text) in lines.iter().enumerate() {
+            let color = if i == row {
+                Color::RGB(64, 145, 108)
+            } else {
+                Color::RGB(202, 210, 197)
+            };
+            let surface = font.render(text).blended(color).unwrap();
+            let texture = texture_creator.create_texture_from_surface(&surface).unwrap();
+            let q = texture.query();
+            canvas.copy(&texture, None, Some(Rect::new(120, 150 + (i as i32) * 60, q.width.min(720), q.height))).unwrap();
+        }
+
+        canvas.present();
+
+        for event in event_pump.poll_iter() {
+            let delta: i64 = match event {
+                Event::Quit { .. } | Event::KeyDown { keycode: Some(Keycode::Escape), .. } => return None,
+                Event::KeyDown { keycode: Some(Keycode::Return), .. } => return Some(cfg),
+                Event::KeyDown { keycode: Some(Keycode::R), .. } => { cfg.seed = random_seed(); 0 }
+                Event::KeyDown { keycode: Some(Keycode::Up), .. } => { row = row.saturating_sub(1); 0 }
+                Event::KeyDown { keycode: Some(Keycode::Down), .. } => { row = (row + 1).min(2); 0 }
+                Event::KeyDown { keycode: Some(Keycode::Left), .. } => -1,
+                Event::KeyDown { keycode: Some(Keycode::Right), .. } => 1,
+                _ => 0,
+            };
+            if delta == 0 { continue; }
+            match row {
+                0 => {
+                    let all = Algorithm::ALL;
+                    let i = all.iter().position(|&a| a == cfg.algorithm).unwrap_or(0) as i64;
+                    cfg.algorithm = all[(i + delta).rem_euclid(all.len() as i64) as usize];
+                }
+                1 => cfg.seed = cfg.seed.wrapping_add_signed(delta),
+                _ => {
+                    let size = (cfg.width + delta as i32).clamp(4, 30);
+                    cfg.width = size;
+                    cfg.height = size;
                 }
+            }
+        }
+
+        std::thread::sleep(Duration::from_millis(16));
+    }
+}
+
+/// Opciones del jugador: por ahora el campo de visión. Los cambios se aplican al volver.
+pub fn show_settings_menu(
+    canvas: &mut Canvas<Window>,
+    texture_creator: &TextureCreator<WindowContext>,
+    font: &Font,
+    event_pump: &mut EventPump,
+    settings: &mut Settings,
+    aspect: f32, // ancho / alto de la ventana, para mostrar el campo horizontal
+) {
+    loop {
+        canvas.set_draw_color(Color::RGB(27, 67, 50));
+        canvas.clear();
+
+        let horizontal = 2.0 * (aspect * (settings.fov.to_radians() / 2.0).tan()).atan();
+        let lines = [
+            format!("Campo de visión: < {:.0}° >", settings.fov),
+            format!("Horizontal: {:.0}°", horizontal.to_degrees()),
+            "ENTER o ESC volver".to_string(),
+        ];
+        for (i, text) in lines.iter().enumerate() {
+            let color = if i == 0 {
+                Color::RGB(64, 145, 108)
+            } else {
+                Color::RGB(202, 210, 197)
+            };
+            let surface = font.render(text).blended(color).unwrap();
+            let texture = texture_creator.create_texture_from_surface(&surface).unwrap();
+            let q = texture.query();
+            canvas.copy(&texture, None, Some(Rect::new(120, 150 + (i as i32) * 60, q.width.min(720), q.height))).unwrap();
+        }
+
+        canvas.present();
+
+        for event in event_pump.poll_iter() {
+            match event {
+                Event::Quit { .. }
+                | Event::KeyDown { keycode: Some(Keycode::Escape | Keycode::Return), .. } => return,
+                Event::KeyDown { keycode: Some(Keycode::Left), .. } => settings.fov = (settings.fov.round() - 5.0).max(30.0),
+                Event::KeyDown { keycode: Some(Keycode::Right), .. } => settings.fov = (settings.fov.round() + 5.0).min(120.0),
                 _ => {}
             }
         }
+
+        std::thread::sleep(Duration::from_millis(16));
     }
 }
 
+fn random_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64 % 100_000)
+        .unwrap_or(1)
+}
+
+/// Resultado del nivel que muestra la pantalla de victoria
+pub struct VictorySummary {
+    pub time: f32, // incluye la penalización por pistas
+    pub hint_penalty: f32,
+    pub par: Option<f32>,
+    pub new_record: bool,
+    pub has_next: bool, // hay un siguiente nivel desbloqueado
+}
+
+/// Lo que eligió el jugador en la pantalla de victoria
+pub enum VictoryChoice {
+    Next,
+    Menu,
+    Quit,
+}
+
 /// Pantalla de victoria
 pub fn show_victory_screen(
     canvas: &mut Canvas<Window>,
     texture_creator: &TextureCreator<WindowContext>,
     font: &Font,
     event_pump: &mut sdl2::EventPump,
-) -> bool {
+    summary: &VictorySummary,
+) -> VictoryChoice {
     use sdl2::render::Texture;
     use sdl2::render::TextureQuery;
     use std::time::Duration;
@@ -130,6 +391,30 @@ pub fn show_victory_screen(
     // Posiciones centradas
     let msg_x = ((win_width - msg_width) / 2) as i32;
     let prompt_x = ((win_width - prompt_width) / 2) as i32;
+    // Tiempo y opciones, a media escala sobre la parte baja de la imagen
+    let mut stats = format!("Tiempo: {}", format_time(summary.time));
+    if let Some(par) = summary.par {
+        stats += &format!("   Par: {}", format_time(par));
+    }
+    if summary.hint_penalty > 0.0 {
+        stats += &format!("   Pistas: +{}", format_time(summary.hint_penalty));
+    }
+    if summary.new_record {
+        stats += "   ¡Nuevo récord!";
+    }
+    let options = if summary.has_next {
+        "ENTER siguiente nivel - M menú - ESC salir"
+    } else {
+        "ENTER menú - ESC salir"
+    };
+    let footer: Vec<Texture> = [(stats.as_str(), Color::RGB(255, 255, 0)), (options, Color::RGB(0, 255, 0))]
+        .into_iter()
+        .map(|(text, color)| {
+            let surface = font.render(text).blended(color).unwrap();
+            texture_creator.create_texture_from_surface(&surface).unwrap()
+        })
+        .collect();
+
     let monkey_dest = Rect::new(
         220 as i32, // Centrado horizontal
         0,                            // Altura
@@ -145,14 +430,24 @@ pub fn show_victory_screen(
         canvas.copy(&monkey_texture, None, Some(monkey_dest)).unwrap();
         //canvas.copy(&texture, None, Some(Rect::new(msg_x, 180, msg_width, msg_height))).unwrap();
         //canvas.copy(&prompt_texture, None, Some(Rect::new(prompt_x, 250, prompt_width, prompt_height))).unwrap();
+        for (i, texture) in footer.iter().enumerate() {
+            let q = texture.query();
+            let (w, h) = (q.width / 2, q.height / 2);
+            let y = win_height as i32 - 70 + i as i32 * (h as i32 + 6);
+            canvas.set_draw_color(Color::RGB(20, 40, 20));
+            canvas.fill_rect(Rect::new((win_width as i32 - w as i32) / 2 - 6, y - 2, w + 12, h + 4)).unwrap();
+            canvas.copy(texture, None, Some(Rect::new((win_width as i32 - w as i32) / 2, y, w, h))).unwrap();
+        }
 
         canvas.present();
 
         for event in event_pump.poll_iter() {
             match event {
-                Event::KeyDown { keycode: Some(Keycode::Return), .. } => return true,
+                Event::KeyDown { keycode: Some(Keycode::Return), .. } if summary.has_next => return VictoryChoice::Next,
+                Event::KeyDown { keycode: Some(Keycode::Return), .. } |
+                Event::KeyDown { keycode: Some(Keycode::M), .. } => return VictoryChoice::Menu,
                 Event::KeyDown { keycode: Some(Keycode::Escape), .. } |
-                Event::Quit { .. } => return false,
+                Event::Quit { .. } => return VictoryChoice::Quit,
                 _ => {}
             }
         }
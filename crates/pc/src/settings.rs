@@ -0,0 +1,34 @@
+use std::fs;
+use std::io;
+
+use raycaster_engine::player::DEFAULT_FOV;
+
+/// Opciones del jugador, guardadas como líneas `clave = valor` igual que el progreso.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Settings {
+    pub fov: f32, // campo de visión vertical, en grados
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self { fov: DEFAULT_FOV }
+    }
+}
+
+impl Settings {
+    /// Lee las opciones; las claves que faltan o no se entienden quedan por defecto.
+    pub fn load(path: &str) -> Self {
+        let mut settings = Self::default();
+        for line in fs::read_to_string(path).unwrap_or_default().lines() {
+            let Some((key, value)) = line.split_once('=') else { continue };
+            if let ("fov", Ok(fov)) = (key.trim(), value.trim().parse::<f32>()) {
+                settings.fov = fov.clamp(30.0, 120.0);
+            }
+        }
+        settings
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, format!("fov = {}\n", self.fov))
+    }
+}